@@ -0,0 +1,229 @@
+use crate::{
+    Evaluator, Heuristic,
+    turns::{Turn, candidate_turns},
+};
+use convoy::{Action, ActionOutcome, Game, Player, agent::Agent};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// The score of a won game, beyond any evaluation
+const WIN: i32 = 1 << 24;
+const INFINITY: i32 = 1 << 30;
+
+/// Iterative-deepening alpha-beta search over whole turns.
+///
+/// Turns have far too many orderings of commands to search them all, so only a few candidate
+/// turns are considered for each player, built greedily from the commands the evaluator likes
+/// best.
+///
+/// ```
+/// use ai::AlphaBeta;
+/// use convoy::{Action, Game};
+/// use std::time::Duration;
+///
+/// // Out of time before scoring a single command, so the turn is ended straight away
+/// let search = AlphaBeta { time_limit: Some(Duration::ZERO), ..AlphaBeta::default() };
+/// assert_eq!(search.choose_actions(&Game::new()), [Action::EndTurn]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AlphaBeta<E = Heuristic> {
+    pub evaluator: E,
+    /// The most turns looked ahead, counting the current one
+    pub max_depth: u8,
+    /// Stops searching once this much time has passed, keeping the last completed depth's choice
+    /// or, if even the first candidate turns were not finished, the best of them so far
+    pub time_limit: Option<Duration>,
+    /// The number of candidate turns starting with a command, besides ending the turn at once
+    pub width: usize,
+}
+
+impl Default for AlphaBeta {
+    fn default() -> Self {
+        Self {
+            evaluator: Heuristic::default(),
+            max_depth: 2,
+            time_limit: None,
+            width: 4,
+        }
+    }
+}
+
+impl<E: Evaluator> AlphaBeta<E> {
+    /// The actions making up the current player's turn, ending with [`Action::EndTurn`] unless
+    /// the game is won before then
+    #[must_use]
+    pub fn choose_actions(&self, game: &Game) -> Vec<Action> {
+        let deadline = self.time_limit.map(|limit| Instant::now() + limit);
+        let game = game.without_history();
+        let player = game.cur_player();
+        let mut turns = candidate_turns(&game, &self.evaluator, self.width, deadline);
+
+        'deepening: for depth in 1..=self.max_depth {
+            let mut alpha = -INFINITY;
+            let mut best = 0;
+
+            for (index, turn) in turns.iter().enumerate() {
+                let Some(score) =
+                    self.score(&turn.outcome, player, depth, alpha, INFINITY, deadline)
+                else {
+                    break 'deepening;
+                };
+
+                if score > alpha {
+                    alpha = score;
+                    best = index;
+                }
+            }
+
+            // The best turn is searched first at the next depth, where it prunes the most
+            let turn = turns.remove(best);
+            turns.insert(0, turn);
+
+            if alpha >= WIN {
+                break;
+            }
+        }
+
+        turns
+            .into_iter()
+            .next()
+            .map_or_else(|| vec![Action::EndTurn], |turn: Turn| turn.actions)
+    }
+
+    /// The negamax score of `game` for its current player, or None once past the deadline
+    fn search(
+        &self,
+        game: &Game,
+        depth: u8,
+        mut alpha: i32,
+        beta: i32,
+        deadline: Option<Instant>,
+    ) -> Option<i32> {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return None;
+        }
+
+        let player = game.cur_player();
+
+        if depth == 0 {
+            return Some(self.evaluator.evaluate(game, player));
+        }
+
+        let turns = candidate_turns(game, &self.evaluator, self.width, deadline);
+
+        // Turns cut short by the deadline are not worth scoring
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return None;
+        }
+
+        let mut best = -INFINITY;
+
+        for turn in turns {
+            let score = self.score(&turn.outcome, player, depth, alpha, beta, deadline)?;
+
+            best = best.max(score);
+            alpha = alpha.max(score);
+
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        Some(best)
+    }
+
+    /// The score for `player` of the outcome of one of their turns, with `depth` turns left
+    /// including that one
+    fn score(
+        &self,
+        outcome: &ActionOutcome,
+        player: Player,
+        depth: u8,
+        alpha: i32,
+        beta: i32,
+        deadline: Option<Instant>,
+    ) -> Option<i32> {
+        match outcome {
+            // Sooner wins leave more depth, so they score higher
+            ActionOutcome::Winner(winner) if *winner == player => Some(WIN + i32::from(depth)),
+            ActionOutcome::Winner(_) => Some(-WIN - i32::from(depth)),
+            ActionOutcome::Draw => Some(0),
+            ActionOutcome::Ongoing(next) => {
+                Some(-self.search(next, depth - 1, -beta, -alpha, deadline)?)
+            }
+        }
+    }
+}
+
+/// The actions chosen by a default [`AlphaBeta`] search
+///
+/// ```
+/// use convoy::{board::Board, rules::RuleSet, Action, Game};
+///
+/// let board = Board::with_size(6, 4).expect("The size is valid");
+/// let game = Game::with_board(board, RuleSet::default());
+/// let actions = ai::choose_actions(&game);
+///
+/// assert_eq!(actions.last(), Some(&Action::EndTurn));
+/// ```
+#[must_use]
+pub fn choose_actions(game: &Game) -> Vec<Action> {
+    AlphaBeta::default().choose_actions(game)
+}
+
+/// Plays the turns chosen by an [`AlphaBeta`] search one action at a time
+#[derive(Clone, Debug)]
+pub struct AlphaBetaAgent<E = Heuristic> {
+    pub search: AlphaBeta<E>,
+    /// The rest of the chosen turn, each action with the position hash it was chosen for
+    plan: VecDeque<(u64, Action)>,
+}
+
+impl Default for AlphaBetaAgent {
+    fn default() -> Self {
+        Self::new(AlphaBeta::default())
+    }
+}
+
+impl<E> AlphaBetaAgent<E> {
+    #[must_use]
+    pub const fn new(search: AlphaBeta<E>) -> Self {
+        Self {
+            search,
+            plan: VecDeque::new(),
+        }
+    }
+}
+
+impl<E: Evaluator> Agent for AlphaBetaAgent<E> {
+    fn name(&self) -> String {
+        "alpha-beta".to_owned()
+    }
+
+    fn next_action(&mut self, game: &Game) -> Action {
+        if self
+            .plan
+            .front()
+            .is_none_or(|&(hash, _)| hash != game.position_hash())
+        {
+            self.plan.clear();
+
+            let mut game = game.without_history();
+
+            for action in self.search.choose_actions(&game) {
+                self.plan.push_back((game.position_hash(), action.clone()));
+
+                match game.take_action(action) {
+                    Ok(ActionOutcome::Ongoing(next)) => game = next,
+                    _ => break,
+                }
+            }
+        }
+
+        self.plan
+            .pop_front()
+            .map_or(Action::EndTurn, |(_, action)| action)
+    }
+}
@@ -0,0 +1,73 @@
+use convoy::{Game, Player, analysis::Weights};
+
+/// Scores positions for a search, higher being better for `player`
+pub trait Evaluator {
+    fn evaluate(&self, game: &Game, player: Player) -> i32;
+}
+
+impl<F: Fn(&Game, Player) -> i32> Evaluator for F {
+    fn evaluate(&self, game: &Game, player: Player) -> i32 {
+        self(game, player)
+    }
+}
+
+/// Scores the difference between both players over the position's [`Analysis`] and a handful of
+/// further features, each scaled by its weight
+///
+/// [`Analysis`]: convoy::analysis::Analysis
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Heuristic {
+    /// Weights of the components of [`Game::analysis`]
+    pub analysis: Weights,
+    /// Per unit of money
+    pub money: i32,
+    /// Per tile covered by the logistics network
+    pub control: i32,
+    /// Per square of the ranks each piece has advanced from its own baseline, so that pieces
+    /// closing in on the enemy baseline count for more
+    pub advance: i32,
+}
+
+impl Default for Heuristic {
+    fn default() -> Self {
+        Self {
+            analysis: Weights::default(),
+            money: 10,
+            control: 2,
+            advance: 1,
+        }
+    }
+}
+
+impl Heuristic {
+    /// Scores the features left out of the analysis
+    fn score(&self, game: &Game, player: Player) -> i32 {
+        let board = game.board();
+        let control = i32::try_from(game.logistics_network(player).len()).unwrap_or(i32::MAX);
+        let last_rank = board.ranks() - 1;
+        let advance: i32 = board
+            .coords()
+            .filter(|&coord| {
+                board[coord]
+                    .piece_option
+                    .is_some_and(|piece| piece.owner == player)
+            })
+            .map(|coord| {
+                i32::from(match player {
+                    Player::P1 => coord.rank,
+                    Player::P2 => last_rank - coord.rank,
+                })
+                .pow(2)
+            })
+            .sum();
+
+        self.money * i32::from(game[player]) + self.control * control + self.advance * advance
+    }
+}
+
+impl Evaluator for Heuristic {
+    fn evaluate(&self, game: &Game, player: Player) -> i32 {
+        game.analysis().score(player, &self.analysis).total() + self.score(game, player)
+            - self.score(game, player.opponent())
+    }
+}
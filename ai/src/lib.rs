@@ -0,0 +1,10 @@
+//! Computer opponents for Convoy.
+
+pub mod alpha_beta;
+pub mod evaluation;
+pub mod mcts;
+mod turns;
+
+pub use alpha_beta::{AlphaBeta, AlphaBetaAgent, choose_actions};
+pub use evaluation::{Evaluator, Heuristic};
+pub use mcts::Mcts;
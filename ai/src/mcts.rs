@@ -0,0 +1,376 @@
+use crate::{Evaluator, Heuristic};
+use convoy::{Action, ActionOutcome, Game, Player, agent::Agent, rng::Rng};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Monte Carlo tree search over single actions.
+///
+/// Each iteration walks down the tree by UCT, expands one untried action, plays the game out for
+/// a few turns and backs the result up. The tree is kept between calls, so searching after the
+/// opponent's turn carries on from whatever was learned about it.
+///
+/// With only an iteration budget, the same seed always chooses the same actions.
+///
+/// ```
+/// use ai::Mcts;
+/// use convoy::{board::Board, rules::RuleSet, Game};
+///
+/// let board = Board::with_size(6, 4).expect("The size is valid");
+/// let game = Game::with_board(board, RuleSet::default());
+/// let choose = || {
+///     let mut mcts = Mcts::new(7);
+///     mcts.iterations = Some(50);
+///     mcts.choose_actions(&game)
+/// };
+///
+/// assert_eq!(choose(), choose());
+///
+/// // Without any limit, the default iteration budget still applies
+/// let mut mcts = Mcts::new(7);
+/// mcts.iterations = None;
+/// assert!(!mcts.choose_actions(&game).is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Mcts<E = Heuristic> {
+    /// Scores playouts which end before the game does
+    pub evaluator: E,
+    /// Iterations run per action chosen, [`Mcts::DEFAULT_ITERATIONS`] if there is no time limit
+    /// either
+    pub iterations: Option<u32>,
+    /// Time spent per action chosen
+    pub time_limit: Option<Duration>,
+    /// How much UCT favours rarely tried actions
+    pub exploration: f64,
+    /// Turns played out before the evaluator scores the position
+    pub playout_turns: u16,
+    /// Actions sampled at each playout step, taking the one the evaluator likes best. With 1,
+    /// playouts are fully random.
+    pub playout_samples: usize,
+    rng: Rng,
+    tree: Option<Tree>,
+}
+
+impl Mcts {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self::with_evaluator(Heuristic::default(), seed)
+    }
+}
+
+impl<E: Evaluator> Mcts<E> {
+    /// The evaluation score at which a playout counts as three quarters of a win
+    const SCORE_SCALE: f64 = 100.0;
+    /// The iterations run per action chosen when no limit is set, so that a search always ends
+    pub const DEFAULT_ITERATIONS: u32 = 200;
+    /// Stops a playout stuck within a turn, which only happens with odd rules
+    const MAX_PLAYOUT_ACTIONS: usize = 2000;
+
+    #[must_use]
+    pub const fn with_evaluator(evaluator: E, seed: u64) -> Self {
+        Self {
+            evaluator,
+            iterations: Some(Self::DEFAULT_ITERATIONS),
+            time_limit: None,
+            exploration: std::f64::consts::SQRT_2,
+            playout_turns: 2,
+            playout_samples: 4,
+            rng: Rng::new(seed),
+            tree: None,
+        }
+    }
+
+    /// The current player's next action
+    pub fn choose_action(&mut self, game: &Game) -> Action {
+        let deadline = self.time_limit.map(|limit| Instant::now() + limit);
+        let iterations = match (self.iterations, deadline) {
+            (None, None) => Some(Self::DEFAULT_ITERATIONS),
+            (iterations, _) => iterations,
+        };
+        let mut tree = self
+            .tree
+            .take()
+            .and_then(|tree| tree.subtree(game.position_hash()))
+            .unwrap_or_else(|| Tree::new(game.without_history()));
+
+        for iteration in 0.. {
+            if iterations.is_some_and(|iterations| iteration >= iterations)
+                || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                break;
+            }
+
+            self.iterate(&mut tree);
+        }
+
+        let best = tree.nodes[0]
+            .children
+            .iter()
+            .max_by_key(|&&(_, child)| tree.nodes[child].visits)
+            .map(|(action, child)| (action.clone(), *child));
+
+        match best {
+            Some((action, child)) => {
+                self.tree = Some(tree.rerooted(child));
+                action
+            }
+            None => Action::EndTurn,
+        }
+    }
+
+    /// The actions making up the current player's turn, ending with [`Action::EndTurn`] unless
+    /// the game is won before then
+    pub fn choose_actions(&mut self, game: &Game) -> Vec<Action> {
+        let mut actions = Vec::new();
+        let mut game = game.without_history();
+
+        loop {
+            let action = self.choose_action(&game);
+            actions.push(action.clone());
+
+            match game.take_action(action) {
+                Ok(ActionOutcome::Ongoing(next)) if actions.last() != Some(&Action::EndTurn) => {
+                    game = next;
+                }
+                _ => return actions,
+            }
+        }
+    }
+
+    fn iterate(&mut self, tree: &mut Tree) {
+        let mut path = vec![0];
+        let mut index = 0;
+
+        while tree.nodes[index].untried.is_empty() && !tree.nodes[index].children.is_empty() {
+            index = self.select(tree, index);
+            path.push(index);
+        }
+
+        if !tree.nodes[index].untried.is_empty() {
+            let untried = &mut tree.nodes[index].untried;
+            let action = untried.swap_remove(self.rng.below(untried.len()));
+
+            if let State::Ongoing(game) = &tree.nodes[index].state {
+                let mover = game.cur_player();
+
+                if let Ok(outcome) = Box::new((**game).clone()).take_action(action.clone()) {
+                    let child = tree.push(outcome, mover);
+                    tree.nodes[index].children.push((action, child));
+                    index = child;
+                    path.push(index);
+                }
+            }
+        }
+
+        let reward = match &tree.nodes[index].state {
+            State::Ongoing(game) => self.playout((**game).clone()),
+            &State::Over(winner) => reward(winner),
+        };
+
+        for index in path {
+            let node = &mut tree.nodes[index];
+            node.visits += 1;
+            node.reward += match node.mover {
+                Player::P1 => reward,
+                Player::P2 => 1.0 - reward,
+            };
+        }
+    }
+
+    /// The child of `parent` with the highest upper confidence bound
+    fn select(&self, tree: &Tree, parent: usize) -> usize {
+        let log_visits = f64::from(tree.nodes[parent].visits).ln();
+        let bound = |child: usize| {
+            let node = &tree.nodes[child];
+            let visits = f64::from(node.visits);
+
+            self.exploration
+                .mul_add((log_visits / visits).sqrt(), node.reward / visits)
+        };
+
+        tree.nodes[parent]
+            .children
+            .iter()
+            .map(|&(_, child)| child)
+            .max_by(|&a, &b| bound(a).total_cmp(&bound(b)))
+            .expect("Only nodes with children are selected from")
+    }
+
+    /// Plays `game` out for a few turns, returning [`Player::P1`]'s reward
+    fn playout(&mut self, mut game: Game) -> f64 {
+        let mut turns = 0;
+
+        for _ in 0..Self::MAX_PLAYOUT_ACTIONS {
+            if turns >= self.playout_turns {
+                break;
+            }
+
+            let actions = game.candidate_actions();
+            let action = self.playout_action(&game, &actions);
+
+            if action == Action::EndTurn {
+                turns += 1;
+            }
+
+            match Box::new(game.clone()).take_action(action) {
+                Ok(ActionOutcome::Ongoing(next)) => game = *next,
+                Ok(ActionOutcome::Draw) => return reward(None),
+                Ok(ActionOutcome::Winner(winner)) => return reward(Some(winner)),
+                Err(_) => break,
+            }
+        }
+
+        let score = f64::from(self.evaluator.evaluate(&game, Player::P1));
+
+        1.0 / (1.0 + (-score * 3.0_f64.ln() / Self::SCORE_SCALE).exp())
+    }
+
+    /// The best of a few random actions according to the evaluator
+    fn playout_action(&mut self, game: &Game, actions: &[Action]) -> Action {
+        let player = game.cur_player();
+        let samples: Vec<&Action> = (0..self.playout_samples.max(1))
+            .map(|_| &actions[self.rng.below(actions.len())])
+            .collect();
+
+        if samples.len() == 1 {
+            return samples[0].clone();
+        }
+
+        samples
+            .into_iter()
+            .max_by_key(
+                |&action| match Box::new(game.clone()).take_action(action.clone()) {
+                    Ok(ActionOutcome::Ongoing(next)) => self.evaluator.evaluate(&next, player),
+                    Ok(ActionOutcome::Winner(winner)) if winner == player => i32::MAX,
+                    Ok(ActionOutcome::Draw) => 0,
+                    _ => i32::MIN,
+                },
+            )
+            .cloned()
+            .unwrap_or(Action::EndTurn)
+    }
+}
+
+impl<E: Evaluator> Agent for Mcts<E> {
+    fn name(&self) -> String {
+        "mcts".to_owned()
+    }
+
+    fn next_action(&mut self, game: &Game) -> Action {
+        self.choose_action(game)
+    }
+}
+
+/// [`Player::P1`]'s reward for a finished game
+const fn reward(winner: Option<Player>) -> f64 {
+    match winner {
+        Some(Player::P1) => 1.0,
+        Some(Player::P2) => 0.0,
+        None => 0.5,
+    }
+}
+
+#[derive(Clone, Debug)]
+enum State {
+    Ongoing(Box<Game>),
+    /// The winner, if there is one
+    Over(Option<Player>),
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    state: State,
+    /// The player whose action led here
+    mover: Player,
+    untried: Vec<Action>,
+    children: Vec<(Action, usize)>,
+    visits: u32,
+    /// The summed rewards of `mover`
+    reward: f64,
+}
+
+/// Nodes stored in a flat list, the root first
+#[derive(Clone, Debug)]
+struct Tree {
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    fn new(game: Box<Game>) -> Self {
+        let mut tree = Self { nodes: Vec::new() };
+        let mover = game.cur_player().opponent();
+        tree.push(ActionOutcome::Ongoing(game), mover);
+
+        tree
+    }
+
+    fn push(&mut self, outcome: ActionOutcome, mover: Player) -> usize {
+        let (state, untried) = match outcome {
+            ActionOutcome::Ongoing(game) => {
+                let untried = game.candidate_actions();
+                (State::Ongoing(game), untried)
+            }
+            ActionOutcome::Draw => (State::Over(None), Vec::new()),
+            ActionOutcome::Winner(winner) => (State::Over(Some(winner)), Vec::new()),
+        };
+
+        self.nodes.push(Node {
+            state,
+            mover,
+            untried,
+            children: Vec::new(),
+            visits: 0,
+            reward: 0.0,
+        });
+
+        self.nodes.len() - 1
+    }
+
+    /// The subtree rooted at the first node reached with `hash`, if any
+    fn subtree(self, hash: u64) -> Option<Self> {
+        let index = self.descendants(0).find(|&index| {
+            matches!(&self.nodes[index].state, State::Ongoing(game) if game.position_hash() == hash)
+        })?;
+
+        Some(self.rerooted(index))
+    }
+
+    /// The subtree rooted at `root`, dropping every other node
+    fn rerooted(mut self, root: usize) -> Self {
+        let order: Vec<usize> = self.descendants(root).collect();
+        let mut new_index = vec![usize::MAX; self.nodes.len()];
+
+        for (new, &old) in order.iter().enumerate() {
+            new_index[old] = new;
+        }
+
+        let nodes = order
+            .into_iter()
+            .map(|old| {
+                let mut node = self.nodes[old].clone();
+                self.nodes[old].children.clear();
+
+                for (_, child) in &mut node.children {
+                    *child = new_index[*child];
+                }
+
+                node
+            })
+            .collect();
+
+        Self { nodes }
+    }
+
+    /// `root` and every node below it, breadth first
+    fn descendants(&self, root: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut queue = VecDeque::from([root]);
+
+        std::iter::from_fn(move || {
+            let index = queue.pop_front()?;
+            queue.extend(self.nodes[index].children.iter().map(|&(_, child)| child));
+
+            Some(index)
+        })
+    }
+}
@@ -0,0 +1,94 @@
+use crate::Evaluator;
+use convoy::{Action, ActionOutcome, Game, Player};
+use std::time::Instant;
+
+/// Every action of one player's turn along with how it left the game
+pub struct Turn {
+    pub actions: Vec<Action>,
+    pub outcome: ActionOutcome,
+}
+
+/// Up to `width` turns for the current player, each starting with a different one of the best
+/// looking commands and then greedily taking whichever command looks best until none improve the
+/// position, along with the turn ending straight away.
+///
+/// Past `deadline`, no more commands are scored and turns end as soon as possible, so the turns
+/// are only the best found so far.
+pub fn candidate_turns(
+    game: &Game,
+    evaluator: &impl Evaluator,
+    width: usize,
+    deadline: Option<Instant>,
+) -> Vec<Turn> {
+    let player = game.cur_player();
+    let mut commands = scored_commands(game, evaluator, player, deadline);
+    commands.sort_by_key(|&(score, ..)| std::cmp::Reverse(score));
+
+    let mut turns: Vec<Turn> = commands
+        .into_iter()
+        .take(width)
+        .map(|(_, action, outcome)| {
+            finish_greedily(vec![action], outcome, evaluator, player, deadline)
+        })
+        .collect();
+
+    turns.push(Turn {
+        actions: vec![Action::EndTurn],
+        outcome: Box::new(game.clone()).end_turn(),
+    });
+
+    turns
+}
+
+fn finish_greedily(
+    mut actions: Vec<Action>,
+    mut outcome: ActionOutcome,
+    evaluator: &impl Evaluator,
+    player: Player,
+    deadline: Option<Instant>,
+) -> Turn {
+    while let ActionOutcome::Ongoing(game) = outcome {
+        let current = evaluator.evaluate(&game, player);
+        let best = scored_commands(&game, evaluator, player, deadline)
+            .into_iter()
+            .max_by_key(|&(score, ..)| score)
+            .filter(|&(score, ..)| score > current);
+
+        if let Some((_, action, next)) = best {
+            actions.push(action);
+            outcome = next;
+        } else {
+            actions.push(Action::EndTurn);
+            outcome = game.end_turn();
+            break;
+        }
+    }
+
+    Turn { actions, outcome }
+}
+
+/// Every command the current player can take with the score of where it leads, leaving out
+/// commands which lose the game and any not scored by `deadline`
+fn scored_commands(
+    game: &Game,
+    evaluator: &impl Evaluator,
+    player: Player,
+    deadline: Option<Instant>,
+) -> Vec<(i32, Action, ActionOutcome)> {
+    game.candidate_actions()
+        .into_iter()
+        .filter(|action| matches!(action, Action::Command(_)))
+        .take_while(|_| deadline.is_none_or(|deadline| Instant::now() < deadline))
+        .filter_map(|action| {
+            let outcome = Box::new(game.clone()).take_action(action.clone()).ok()?;
+            let score = match &outcome {
+                ActionOutcome::Ongoing(next) => evaluator.evaluate(next, player),
+                ActionOutcome::Draw => 0,
+                ActionOutcome::Winner(winner) if *winner == player => i32::MAX,
+                ActionOutcome::Winner(_) => return None,
+            };
+
+            Some((score, action, outcome))
+        })
+        .collect()
+}
@@ -0,0 +1,182 @@
+//! Plays agents against each other and reports how they did.
+
+mod report;
+
+use convoy::{
+    ActionOutcome, Game, Player,
+    agent::{Agent, RandomAgent},
+    board::Board,
+    generator::GeneratorSettings,
+    map::Map,
+    rules::RuleSet,
+};
+use report::{GameResult, Report};
+use std::{env, fs, process::ExitCode, time::Instant};
+
+const USAGE: &str = "\
+Usage: arena <AGENT> <AGENT> [OPTIONS]
+
+Agents: random, alpha-beta, mcts
+
+Options:
+  --games <N>        Games to play, alternating who starts [default: 2]
+  --map <FILE>       A map to play on, repeat to cycle through several [default: generated maps]
+  --seed <N>         Seeds the agents and generated maps [default: 0]
+  --max-turns <N>    Turns before a game is drawn [default: 200]
+  --json             Report as JSON instead of a table";
+
+struct Options {
+    agents: [String; 2],
+    games: u32,
+    maps: Vec<(String, Board)>,
+    seed: u64,
+    max_turns: u16,
+    json: bool,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_options(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut report = Report::new(options.agents.clone());
+    let mut rules = RuleSet::default();
+    rules.end_conditions.draw.turn_limit = Some(options.max_turns);
+
+    for game in 0..options.games {
+        let seed = options.seed.wrapping_add(u64::from(game));
+        // Each map is played twice in a row, once with each agent starting
+        let pair = u64::from(game / 2);
+        let (map_name, board) = if options.maps.is_empty() {
+            let map_seed = options.seed.wrapping_add(pair);
+            let board = GeneratorSettings::default()
+                .generate(map_seed)
+                .expect("The default settings always generate a board");
+
+            (format!("seed {map_seed}"), board)
+        } else {
+            let index = usize::try_from(pair).unwrap_or(usize::MAX) % options.maps.len();
+            options.maps[index].clone()
+        };
+
+        let first = usize::from(game % 2 == 1);
+        let mut agents = [
+            make_agent(&options.agents[first], seed).expect("Agent names were checked"),
+            make_agent(&options.agents[1 - first], seed).expect("Agent names were checked"),
+        ];
+        let result = play(
+            &mut agents,
+            Game::with_board(board, rules.clone()),
+            &mut report,
+            first,
+        );
+
+        report.add_game(map_name, first, result);
+    }
+
+    if options.json {
+        println!("{}", report.to_json());
+    } else {
+        print!("{}", report.to_table());
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Plays a game between `agents`, the first playing [`Player::P1`]. `first` is the index in the
+/// report of the agent playing [`Player::P1`].
+fn play(
+    agents: &mut [Box<dyn Agent>; 2],
+    mut game: Box<Game>,
+    report: &mut Report,
+    first: usize,
+) -> GameResult {
+    loop {
+        let player = game.cur_player();
+        let seat = match player {
+            Player::P1 => 0,
+            Player::P2 => 1,
+        };
+        let reported = if seat == 0 { first } else { 1 - first };
+
+        let start = Instant::now();
+        let action = agents[seat].next_action(&game);
+        report.add_move(reported, start.elapsed());
+
+        // Counting the turn being played
+        let turns = game.turn().saturating_add(1);
+
+        game = match game.take_action(action) {
+            Ok(ActionOutcome::Ongoing(next)) => next,
+            Ok(ActionOutcome::Draw) => return GameResult::Draw { turns },
+            Ok(ActionOutcome::Winner(winner)) => {
+                return GameResult::Winner {
+                    player: winner,
+                    turns,
+                };
+            }
+            Err(_) => return GameResult::Forfeit { player, turns },
+        };
+    }
+}
+
+fn make_agent(name: &str, seed: u64) -> Option<Box<dyn Agent>> {
+    match name {
+        "random" => Some(Box::new(RandomAgent::new(seed))),
+        "alpha-beta" => Some(Box::new(ai::AlphaBetaAgent::default())),
+        "mcts" => Some(Box::new(ai::Mcts::new(seed))),
+        _ => None,
+    }
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut agents = Vec::new();
+    let mut options = Options {
+        agents: Default::default(),
+        games: 2,
+        maps: Vec::new(),
+        seed: 0,
+        max_turns: 200,
+        json: false,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{name} needs a value"));
+
+        match arg.as_str() {
+            "--games" => options.games = parse_number("--games", &value("--games")?)?,
+            "--seed" => options.seed = parse_number("--seed", &value("--seed")?)?,
+            "--max-turns" => {
+                options.max_turns = parse_number("--max-turns", &value("--max-turns")?)?;
+            }
+            "--map" => {
+                let path = value("--map")?;
+                let text = fs::read_to_string(&path).map_err(|error| format!("{path}: {error}"))?;
+                let map: Map = text.parse().map_err(|error| format!("{path}: {error}"))?;
+                let name = map.get("name").map_or_else(|| path.clone(), str::to_owned);
+
+                options.maps.push((name, map.board));
+            }
+            "--json" => options.json = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown option {arg}")),
+            _ if make_agent(&arg, 0).is_none() => return Err(format!("Unknown agent {arg}")),
+            _ => agents.push(arg),
+        }
+    }
+
+    options.agents = agents
+        .try_into()
+        .map_err(|_| "Exactly two agents are needed".to_owned())?;
+
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{name} needs a number, not {value}"))
+}
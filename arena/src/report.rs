@@ -0,0 +1,232 @@
+use convoy::Player;
+use std::{fmt::Write, time::Duration};
+
+/// How a game ended, with the turns it lasted
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GameResult {
+    Winner {
+        player: Player,
+        turns: u16,
+    },
+    Draw {
+        turns: u16,
+    },
+    /// `player` took an illegal action and lost
+    Forfeit {
+        player: Player,
+        turns: u16,
+    },
+}
+
+impl GameResult {
+    const fn turns(self) -> u16 {
+        match self {
+            Self::Winner { turns, .. } | Self::Draw { turns } | Self::Forfeit { turns, .. } => {
+                turns
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct AgentStats {
+    name: String,
+    wins: u32,
+    losses: u32,
+    draws: u32,
+    forfeits: u32,
+    moves: u32,
+    thinking: Duration,
+    longest_move: Duration,
+}
+
+impl AgentStats {
+    fn average_move(&self) -> Duration {
+        self.thinking.checked_div(self.moves).unwrap_or_default()
+    }
+}
+
+struct GameRecord {
+    map: String,
+    /// The index of the agent playing [`Player::P1`]
+    first: usize,
+    result: GameResult,
+}
+
+pub struct Report {
+    agents: [AgentStats; 2],
+    games: Vec<GameRecord>,
+}
+
+impl Report {
+    pub fn new(names: [String; 2]) -> Self {
+        Self {
+            agents: names.map(|name| AgentStats {
+                name,
+                ..AgentStats::default()
+            }),
+            games: Vec::new(),
+        }
+    }
+
+    pub fn add_move(&mut self, agent: usize, time: Duration) {
+        let stats = &mut self.agents[agent];
+        stats.moves += 1;
+        stats.thinking += time;
+        stats.longest_move = stats.longest_move.max(time);
+    }
+
+    /// Records a game in which the agent at `first` played [`Player::P1`]
+    pub fn add_game(&mut self, map: String, first: usize, result: GameResult) {
+        let agent = |player| match player {
+            Player::P1 => first,
+            Player::P2 => 1 - first,
+        };
+
+        match result {
+            GameResult::Winner { player, .. } => {
+                self.agents[agent(player)].wins += 1;
+                self.agents[agent(player.opponent())].losses += 1;
+            }
+            GameResult::Draw { .. } => self.agents.iter_mut().for_each(|stats| stats.draws += 1),
+            GameResult::Forfeit { player, .. } => {
+                self.agents[agent(player)].forfeits += 1;
+                self.agents[agent(player.opponent())].wins += 1;
+            }
+        }
+
+        self.games.push(GameRecord { map, first, result });
+    }
+
+    fn average_turns(&self) -> f64 {
+        if self.games.is_empty() {
+            return 0.0;
+        }
+
+        let turns: u32 = self
+            .games
+            .iter()
+            .map(|game| u32::from(game.result.turns()))
+            .sum();
+
+        f64::from(turns) / f64::from(u32::try_from(self.games.len()).unwrap_or(u32::MAX))
+    }
+
+    pub fn to_table(&self) -> String {
+        let mut table = String::new();
+        let width = self
+            .agents
+            .iter()
+            .map(|stats| stats.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("Agent".len());
+
+        writeln!(
+            table,
+            "{:width$}  {:>5}  {:>6}  {:>5}  {:>8}  {:>12}  {:>12}",
+            "Agent", "Wins", "Losses", "Draws", "Forfeits", "Average move", "Longest move"
+        )
+        .expect("Writing to a String never fails");
+
+        for stats in &self.agents {
+            writeln!(
+                table,
+                "{:width$}  {:>5}  {:>6}  {:>5}  {:>8}  {:>12}  {:>12}",
+                stats.name,
+                stats.wins,
+                stats.losses,
+                stats.draws,
+                stats.forfeits,
+                format!("{:.2?}", stats.average_move()),
+                format!("{:.2?}", stats.longest_move),
+            )
+            .expect("Writing to a String never fails");
+        }
+
+        writeln!(
+            table,
+            "\n{} games, lasting {:.1} turns on average",
+            self.games.len(),
+            self.average_turns()
+        )
+        .expect("Writing to a String never fails");
+
+        table
+    }
+
+    pub fn to_json(&self) -> String {
+        let agents = self
+            .agents
+            .iter()
+            .map(|stats| {
+                format!(
+                    "{{\"name\":{},\"wins\":{},\"losses\":{},\"draws\":{},\"forfeits\":{},\
+                     \"moves\":{},\"average_move_ms\":{:.3},\"longest_move_ms\":{:.3}}}",
+                    json_string(&stats.name),
+                    stats.wins,
+                    stats.losses,
+                    stats.draws,
+                    stats.forfeits,
+                    stats.moves,
+                    stats.average_move().as_secs_f64() * 1000.0,
+                    stats.longest_move.as_secs_f64() * 1000.0,
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let games = self
+            .games
+            .iter()
+            .map(|game| {
+                let name = |player| {
+                    let index = match player {
+                        Player::P1 => game.first,
+                        Player::P2 => 1 - game.first,
+                    };
+
+                    json_string(&self.agents[index].name)
+                };
+                let (result, forfeit) = match game.result {
+                    GameResult::Winner { player, .. } => (name(player), false),
+                    GameResult::Draw { .. } => ("null".to_owned(), false),
+                    GameResult::Forfeit { player, .. } => (name(player.opponent()), true),
+                };
+
+                format!(
+                    "{{\"map\":{},\"p1\":{},\"p2\":{},\"winner\":{result},\"forfeit\":{forfeit},\
+                     \"turns\":{}}}",
+                    json_string(&game.map),
+                    name(Player::P1),
+                    name(Player::P2),
+                    game.result.turns(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            "{{\"games\":[{games}],\"agents\":[{agents}],\"average_turns\":{:.1}}}",
+            self.average_turns()
+        )
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from('"');
+
+    for char in s.chars() {
+        match char {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            char if char.is_control() => {
+                write!(escaped, "\\u{:04x}", u32::from(char))
+                    .expect("Writing to a String never fails");
+            }
+            char => escaped.push(char),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
@@ -1,5 +1,6 @@
 use convoy::{
-    board::Board, coord::Coord, coord::Move, pieces::PieceType, tile::Tile, ActionOutcome, Player,
+    analysis::PlayerAnalysis, board::Board, coord::Coord, coord::Move, pieces::PieceType,
+    tile::Tile, Action, ActionOutcome, Command, CommandError, Player,
 };
 use iced::{
     alignment::{Horizontal, Vertical}, color, font::Weight, widget::{button, column, container, container::background, rich_text, row, span, text},
@@ -13,6 +14,8 @@ use std::fmt::Debug;
 pub struct State {
     game: Option<Box<convoy::Game>>,
     action_mode: ActionMode,
+    outcome: Option<ActionOutcome>,
+    error: Option<CommandError>,
 }
 
 impl Default for State {
@@ -20,6 +23,8 @@ impl Default for State {
         Self {
             game: Some(convoy::Game::new()),
             action_mode: ActionMode::default(),
+            outcome: None,
+            error: None,
         }
     }
 }
@@ -29,7 +34,9 @@ pub enum Message {
     ChangeActionMode(ActionMode),
     ChangePieceType(PieceType),
     EndTurn,
+    Redo,
     TileClicked(usize, usize),
+    Undo,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -47,9 +54,9 @@ impl Default for ActionMode {
 
 impl State {
     pub fn update(&mut self, message: Message) {
-        let Some(game) = &mut self.game else {
+        if self.game.is_none() {
             return;
-        };
+        }
 
         match message {
             Message::ChangeActionMode(select_mode) => {
@@ -64,78 +71,98 @@ impl State {
                 }
             }
             Message::EndTurn => {
-                let game = self.game.take().expect("If we're here game is Some");
-                match game.end_turn() {
-                    ActionOutcome::Ongoing(game) => {
-                        self.action_mode = ActionMode::default();
-                        self.game = Some(game);
-                    }
-                    ActionOutcome::Draw => todo!(),
-                    ActionOutcome::Winner(_) => todo!(),
+                self.take_action(Action::EndTurn);
+                self.action_mode = ActionMode::default();
+            }
+            Message::Redo => {
+                if let Some(game) = &mut self.game
+                    && game.redo().is_ok()
+                {
+                    self.action_mode = ActionMode::default();
+                    self.error = None;
                 }
             }
-            Message::TileClicked(row, col) => match &mut self.action_mode {
+            Message::Undo => {
+                if let Some(game) = &mut self.game
+                    && game.undo().is_ok()
+                {
+                    self.action_mode = ActionMode::default();
+                    self.error = None;
+                }
+            }
+            Message::TileClicked(row, col) => match self.action_mode {
                 ActionMode::Move(piece_option) => {
                     let tile_coord = Coord::new(row, col)
                         .expect("A TileClicked message is always a valid coord");
 
                     match piece_option {
-                        &mut Some(piece) if piece == tile_coord => *piece_option = None,
-                        &mut Some(piece) => {
-                            let _ = game.do_move(Move {
+                        Some(piece) if piece == tile_coord => {
+                            self.action_mode = ActionMode::Move(None);
+                        }
+                        Some(piece) => {
+                            self.take_action(Action::Command(Command::Move(Move {
                                 from: piece,
                                 to: tile_coord,
-                            });
-                            *piece_option = None;
+                            })));
+                            self.action_mode = ActionMode::Move(None);
                         }
-                        None => *piece_option = Some(tile_coord),
+                        None => self.action_mode = ActionMode::Move(Some(tile_coord)),
                     }
                 }
-                ActionMode::Purchase(piece_opt) => {
-                    if let &mut Some(piece_type) = piece_opt {
-                        let _ = game.do_purchase(
-                            piece_type,
-                            Coord::new(row, col)
-                                .expect("A TileClicked message is always a valid coord"),
-                        );
-                        if game[game.cur_player()] < piece_type.cost() {
-                            *piece_opt = None;
-                        }
+                ActionMode::Purchase(Some(piece_type)) => {
+                    self.take_action(Action::Command(Command::Purchase(
+                        piece_type,
+                        Coord::new(row, col)
+                            .expect("A TileClicked message is always a valid coord"),
+                    )));
+
+                    if self
+                        .game
+                        .as_ref()
+                        .is_none_or(|game| game[game.cur_player()] < game.rules()[piece_type].cost)
+                    {
+                        self.action_mode = ActionMode::Purchase(None);
                     }
                 }
-                ActionMode::Battle => {}
+                ActionMode::Purchase(None) | ActionMode::Battle => {}
             },
         }
     }
 
-    pub fn view(&self) -> Element<Message> {
+    fn take_action(&mut self, action: Action) {
+        let game = self.game.take().expect("Actions are only taken while there is a game");
+
+        self.error = None;
+
+        match game.take_action(action) {
+            Ok(ActionOutcome::Ongoing(game)) => self.game = Some(game),
+            Ok(outcome) => self.outcome = Some(outcome),
+            Err((game, error)) => {
+                self.game = Some(game);
+                self.error = Some(error);
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
         let Some(game) = &self.game else {
-            return text("Game Over").into();
+            return text(match self.outcome {
+                Some(ActionOutcome::Winner(player)) => format!("{} wins", player_name(player)),
+                Some(ActionOutcome::Draw) => "Draw".to_owned(),
+                _ => "Game Over".to_owned(),
+            })
+            .into();
         };
 
         let board = match self.action_mode {
             ActionMode::Move(piece_option) => {
-                view_move_action_board(game.board(), piece_option, game.cur_player())
+                view_move_action_board(game, piece_option)
             }
-            ActionMode::Purchase(_) => view_purchase_action_board(game.board(), game.cur_player()),
+            ActionMode::Purchase(_) => view_purchase_action_board(game),
             ActionMode::Battle => view_battle_action_board(game.board()),
         };
 
-        let players = row![
-            view_player(
-                Player::P1,
-                game[Player::P1],
-                game.cur_player() == Player::P1
-            ),
-            view_player(
-                Player::P2,
-                game[Player::P2],
-                game.cur_player() == Player::P2
-            )
-        ]
-        .spacing(10)
-        .height(Fill)
-        .align_y(Vertical::Center);
+        let players = view_players(game);
 
         let piece_selectors = container(
             column![
@@ -180,8 +207,24 @@ impl State {
         let end_turn_button =
             container(button("End Turn").on_press(Message::EndTurn)).center_y(Fill);
 
+        let history_buttons = container(view_history_buttons(game)).center_y(Fill);
+
+        let error_text = container(
+            text(self.error.map_or_else(String::new, |error| error.to_string()))
+                .width(180)
+                .align_x(Horizontal::Center),
+        )
+        .center_y(Fill);
+
         let sidebar = container(
-            column![players, piece_selectors, action_selectors, end_turn_button]
+            column![
+                players,
+                piece_selectors,
+                action_selectors,
+                end_turn_button,
+                history_buttons,
+                error_text
+            ]
                 .spacing(15)
                 .align_x(Horizontal::Center)
                 .height(Fill),
@@ -198,33 +241,25 @@ impl State {
 }
 
 fn view_move_action_board(
-    board: &Board,
+    game: &convoy::Game,
     selected_piece: Option<Coord>,
-    cur_turn: Player,
-) -> Element<Message> {
+) -> Element<'_, Message> {
+    let moves: Vec<Move> = game.legal_moves().collect();
+    let size = tile_size(game.board());
+
     let board_tile = |row, col, tile: &Tile| {
+        let coord = Coord::new(row, col).expect("row and col are always a valid Coord");
         let selectable = selected_piece.map_or_else(
-            || {
-                tile.piece_option
-                    .is_some_and(|piece| piece.owner == cur_turn && !piece.exhausted)
-            },
-            |piece| {
-                let coord = Coord::new(row, col).expect("row and col are always a valid Coord");
-
-                piece == coord
-                    || board
-                        .get_moves(piece)
-                        .expect("piece will always index a valid Piece")
-                        .contains(&coord)
-            },
+            || moves.iter().any(|r#move| r#move.from == coord),
+            |piece| piece == coord || moves.contains(&Move { from: piece, to: coord }),
         );
 
-        view_tile(*tile, (row + col) % 2 == 0, selectable, selectable)
+        view_tile(*tile, size, (row + col) % 2 == 0, selectable, selectable)
             .map(move |()| Message::TileClicked(row, col))
     };
 
     column(
-        board
+        game.board()
             .rows()
             .enumerate()
             .map(|(row_index, tile_row): (usize, &[Tile])| {
@@ -238,27 +273,35 @@ fn view_move_action_board(
     .into()
 }
 
-fn view_purchase_action_board(board: &Board, player: Player) -> Element<Message> {
+fn view_purchase_action_board(game: &convoy::Game) -> Element<'_, Message> {
+    let purchasable: Vec<Coord> = game.legal_purchases().map(|(_, coord)| coord).collect();
+    let size = tile_size(game.board());
+
     let tile_row = |(row_index, tile_row): (usize, &[Tile])| {
         row(tile_row.iter().enumerate().map(|(col_index, tile)| {
+            let coord =
+                Coord::new(row_index, col_index).expect("row and col are always a valid Coord");
+
             view_tile(
                 *tile,
+                size,
                 (row_index + col_index) % 2 == 0,
-                tile.produces_troops(player),
-                tile.produces_troops(player) && tile.piece_option.is_none(),
+                tile.produces_troops(game.cur_player()),
+                purchasable.contains(&coord),
             )
             .map(move |()| Message::TileClicked(row_index, col_index))
         }))
         .into()
     };
 
-    column(board.rows().enumerate().map(tile_row)).into()
+    column(game.board().rows().enumerate().map(tile_row)).into()
 }
 
-fn view_battle_action_board(board: &Board) -> Element<Message> {
+fn view_battle_action_board(board: &Board) -> Element<'_, Message> {
+    let size = tile_size(board);
     let tile_row = |(row_index, tile_row): (usize, &[Tile])| {
         row(tile_row.iter().enumerate().map(|(col_index, tile)| {
-            view_tile(*tile, (row_index + col_index) % 2 == 0, false, false)
+            view_tile(*tile, size, (row_index + col_index) % 2 == 0, false, false)
                 .map(move |()| Message::TileClicked(row_index, col_index))
         }))
         .into()
@@ -267,9 +310,21 @@ fn view_battle_action_board(board: &Board) -> Element<Message> {
     column(board.rows().enumerate().map(tile_row)).into()
 }
 
-fn view_tile(tile: Tile, light: bool, selected: bool, enabled: bool) -> Element<'static, ()> {
-    const TILE_SIZE: u16 = 30;
+/// Shrinks tiles below their usual size so that large boards still fit the window
+fn tile_size(board: &Board) -> u16 {
+    const BOARD_SIZE: u16 = 630;
+    const MAX_TILE_SIZE: u16 = 30;
 
+    (BOARD_SIZE / u16::from(board.ranks().max(board.files()))).min(MAX_TILE_SIZE)
+}
+
+fn view_tile(
+    tile: Tile,
+    size: u16,
+    light: bool,
+    selected: bool,
+    enabled: bool,
+) -> Element<'static, ()> {
     let background = color!(match (light, selected) {
         (_, true) => 0xba_ca_44,
         (true, _) => 0xee_ee_d2,
@@ -285,8 +340,8 @@ fn view_tile(tile: Tile, light: bool, selected: bool, enabled: bool) -> Element<
         .center(),
     )
     .on_press_maybe(if enabled { Some(()) } else { None })
-    .width(TILE_SIZE)
-    .height(TILE_SIZE)
+    .width(size)
+    .height(size)
     .style(move |_, _| button::Style {
         background: Some(background),
         ..button::Style::default()
@@ -294,12 +349,14 @@ fn view_tile(tile: Tile, light: bool, selected: bool, enabled: bool) -> Element<
     .into()
 }
 
-fn view_player(player: Player, money: u8, is_current: bool) -> Element<'static, Message> {
+fn view_player(
+    player: Player,
+    money: u8,
+    analysis: &PlayerAnalysis,
+    is_current: bool,
+) -> Element<'static, Message> {
     let player_text = rich_text![
-        span(match player {
-            Player::P1 => "Player 1",
-            Player::P2 => "Player 2",
-        })
+        span(player_name(player))
         .font_maybe(if is_current {
             Some(Font {
                 weight: Weight::Bold,
@@ -312,12 +369,65 @@ fn view_player(player: Player, money: u8, is_current: bool) -> Element<'static,
     .width(70)
     .align_x(Horizontal::Center);
 
-    container(column![player_text, rich_text![span(money)]].align_x(Horizontal::Center))
-        .padding(5)
-        .style(|_| background(color!(0x99_99_99)))
+    container(
+        column![
+            player_text,
+            rich_text![span(money)],
+            view_analysis(analysis)
+        ]
+        .align_x(Horizontal::Center),
+    )
+    .padding(5)
+    .style(|_| background(color!(0x99_99_99)))
+    .into()
+}
+
+fn view_players(game: &convoy::Game) -> Element<'_, Message> {
+    let analysis = game.analysis();
+    let players = row![
+        view_player(
+            Player::P1,
+            game[Player::P1],
+            &analysis[Player::P1],
+            game.cur_player() == Player::P1
+        ),
+        view_player(
+            Player::P2,
+            game[Player::P2],
+            &analysis[Player::P2],
+            game.cur_player() == Player::P2
+        )
+    ]
+    .spacing(10)
+    .height(Fill)
+    .align_y(Vertical::Center);
+
+    column![players, text(format!("{} Phase", game.phase()))]
+        .align_x(Horizontal::Center)
         .into()
 }
 
+/// The parts of the position's analysis worth keeping an eye on
+fn view_analysis(analysis: &PlayerAnalysis) -> Element<'static, Message> {
+    column![
+        text(format!("Income +{}", analysis.income)).size(12),
+        text(format!("Material {}", analysis.material)).size(12),
+        text(format!("Unsupplied {}", analysis.unsupplied.len())).size(12),
+        text(format!("Under fire {}", analysis.threatened.len())).size(12),
+        text(format!("Threats {}", analysis.baseline_threats.len())).size(12),
+    ]
+    .padding([5, 0])
+    .align_x(Horizontal::Center)
+    .into()
+}
+
+const fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::P1 => "Player 1",
+        Player::P2 => "Player 2",
+    }
+}
+
 fn view_piece_selector<'a>(
     piece_type: PieceType,
     game: &'a convoy::Game,
@@ -338,7 +448,7 @@ fn view_piece_selector<'a>(
     };
 
     selector
-        .on_press_maybe(if game[game.cur_player()] >= piece_type.cost() {
+        .on_press_maybe(if game[game.cur_player()] >= game.rules()[piece_type].cost {
             Some(Message::ChangePieceType(piece_type))
         } else {
             None
@@ -353,6 +463,15 @@ fn view_piece_selector<'a>(
         .into()
 }
 
+fn view_history_buttons(game: &convoy::Game) -> Element<'static, Message> {
+    row![
+        button("Undo").on_press_maybe(game.can_undo().then_some(Message::Undo)),
+        button("Redo").on_press_maybe(game.can_redo().then_some(Message::Redo))
+    ]
+    .spacing(5)
+    .into()
+}
+
 fn view_action_selector(action_mode: ActionMode, inactive: bool) -> Element<'static, Message> {
     button(
         text(match action_mode {
@@ -36,7 +36,7 @@ impl App {
         }
     }
 
-    pub fn view(&self) -> Element<AppMessage> {
+    pub fn view(&self) -> Element<'_, AppMessage> {
         Element::<AppMessage>::from(container(self.game.view().map(AppMessage::Game)).center(Fill))
             // .explain(iced::color!(0x77_77_77))
     }
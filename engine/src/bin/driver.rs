@@ -0,0 +1,306 @@
+//! Runs two protocol engines as subprocesses against each other, checking every action they send.
+
+use convoy::{Action, ActionOutcome, Game, Player, generator::GeneratorSettings, rules::RuleSet};
+use std::{
+    env,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, ExitCode, Stdio},
+};
+
+const USAGE: &str = "\
+Usage: driver <ENGINE> <ENGINE> [OPTIONS]
+
+Each engine is a command line run through the protocol, e.g. \"engine --agent mcts\"
+
+Options:
+  --games <N>        Games to play, alternating who starts [default: 2]
+  --seed <N>         Seeds the generated maps [default: 0]
+  --movetime <MS>    Time given for each action [default: 1000]
+  --max-turns <N>    Turns before a game is drawn [default: 200]";
+
+struct Options {
+    engines: [String; 2],
+    games: u32,
+    seed: u64,
+    movetime: u64,
+    max_turns: u16,
+}
+
+struct Engine {
+    name: String,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Engine {
+    fn spawn(command_line: &str) -> io::Result<Self> {
+        let mut words = command_line.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| io::Error::other("An engine command is empty"))?;
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(io::Error::other("The engine's pipes were not opened"));
+        };
+
+        let mut engine = Self {
+            name: command_line.to_owned(),
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+        };
+        engine.send("convoy")?;
+
+        loop {
+            let line = engine.receive()?;
+
+            if let Some(name) = line.strip_prefix("id name ") {
+                name.clone_into(&mut engine.name);
+            } else if line == "convoyok" {
+                return Ok(engine);
+            }
+        }
+    }
+
+    fn send(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.stdin, "{line}")?;
+        self.stdin.flush()
+    }
+
+    fn receive(&mut self) -> io::Result<String> {
+        let mut line = String::new();
+
+        if self.stdout.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("{} exited", self.name),
+            ));
+        }
+
+        Ok(line.trim_end().to_owned())
+    }
+
+    /// Waits for every command sent so far to be handled, failing if any of them went wrong
+    fn sync(&mut self) -> io::Result<()> {
+        self.send("isready")?;
+
+        loop {
+            let line = self.receive()?;
+
+            if line == "readyok" {
+                return Ok(());
+            } else if let Some(error) = line.strip_prefix("error ") {
+                return Err(io::Error::other(format!("{}: {error}", self.name)));
+            }
+        }
+    }
+
+    /// Asks for the current player's next action
+    fn go(&mut self, movetime: u64) -> io::Result<Action> {
+        self.send(&format!("go movetime {movetime}"))?;
+
+        loop {
+            let line = self.receive()?;
+
+            if let Some(action) = line.strip_prefix("action ") {
+                return action
+                    .parse()
+                    .map_err(|error| io::Error::other(format!("{}: {error}", self.name)));
+            } else if let Some(error) = line.strip_prefix("error ") {
+                return Err(io::Error::other(format!("{}: {error}", self.name)));
+            }
+        }
+    }
+
+    fn quit(mut self) {
+        // The engine may already be gone, in which case there is nothing left to do
+        let _ = self.send("quit");
+        let _ = self.child.wait();
+    }
+}
+
+enum GameResult {
+    Winner(Player),
+    Draw,
+    /// The player whose engine failed or sent an illegal action
+    Forfeit(Player, String),
+}
+
+fn main() -> ExitCode {
+    let options = match parse_options(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut engines = Vec::new();
+
+    for command_line in &options.engines {
+        match Engine::spawn(command_line) {
+            Ok(engine) => engines.push(engine),
+            Err(error) => {
+                eprintln!("Could not start {command_line}: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let mut rules = RuleSet::default();
+    rules.end_conditions.draw.turn_limit = Some(options.max_turns);
+    // Wins, draws and losses of each engine
+    let mut scores = [[0; 3]; 2];
+
+    for game in 0..options.games {
+        // Each map is played twice in a row, once with each engine starting
+        let map_seed = options.seed.wrapping_add(u64::from(game / 2));
+        let first = usize::from(game % 2 == 1);
+        let board = GeneratorSettings::default()
+            .generate(map_seed)
+            .expect("The default settings always generate a board");
+        let setup = format!("map seed {map_seed}");
+
+        let result = play(
+            &mut engines,
+            Game::with_board(board, rules.clone()),
+            &setup,
+            first,
+            options.movetime,
+        );
+
+        let (text, winner) = match result {
+            GameResult::Winner(player) => (format!("{player:?} wins"), Some(player)),
+            GameResult::Draw => ("Draw".to_owned(), None),
+            GameResult::Forfeit(player, reason) => (
+                format!("{player:?} forfeits ({reason})"),
+                Some(player.opponent()),
+            ),
+        };
+        println!(
+            "Game {} on map seed {map_seed}: {} (P1) vs {} (P2): {text}",
+            game + 1,
+            engines[first].name,
+            engines[1 - first].name,
+        );
+
+        for (seat, &index) in [first, 1 - first].iter().enumerate() {
+            let column = match winner {
+                None => 1,
+                Some(Player::P1) => seat * 2,
+                Some(Player::P2) => 2 - seat * 2,
+            };
+            scores[index][column] += 1;
+        }
+    }
+
+    for (engine, [wins, draws, losses]) in engines.iter().zip(scores) {
+        println!(
+            "{}: {wins} wins, {draws} draws, {losses} losses",
+            engine.name
+        );
+    }
+
+    for engine in engines {
+        engine.quit();
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Plays a game between `engines`, the one at index `first` playing [`Player::P1`]
+fn play(
+    engines: &mut [Engine],
+    mut game: Box<Game>,
+    setup: &str,
+    first: usize,
+    movetime: u64,
+) -> GameResult {
+    let seat = |player| match player {
+        Player::P1 => first,
+        Player::P2 => 1 - first,
+    };
+
+    for player in [Player::P1, Player::P2] {
+        let engine = &mut engines[seat(player)];
+
+        if let Err(error) = engine
+            .send(setup)
+            .and_then(|()| engine.send("newgame"))
+            .and_then(|()| engine.sync())
+        {
+            return GameResult::Forfeit(player, error.to_string());
+        }
+    }
+
+    loop {
+        let player = game.cur_player();
+        let action = match engines[seat(player)].go(movetime) {
+            Ok(action) => action,
+            Err(error) => return GameResult::Forfeit(player, error.to_string()),
+        };
+        let line = format!("action {action}");
+
+        // The action is only ever looked at through `take_action`, which rejects anything
+        // illegal, including squares off the board, and any rejection forfeits the game
+        game = match game.take_action(action) {
+            Ok(ActionOutcome::Ongoing(next)) => next,
+            Ok(ActionOutcome::Draw) => return GameResult::Draw,
+            Ok(ActionOutcome::Winner(winner)) => return GameResult::Winner(winner),
+            Err((_, error)) => {
+                return GameResult::Forfeit(player, format!("{line} is illegal: {error}"));
+            }
+        };
+
+        for player in [Player::P1, Player::P2] {
+            let engine = &mut engines[seat(player)];
+
+            if let Err(error) = engine.send(&line).and_then(|()| engine.sync()) {
+                return GameResult::Forfeit(player, error.to_string());
+            }
+        }
+    }
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut engines = Vec::new();
+    let mut options = Options {
+        engines: Default::default(),
+        games: 2,
+        seed: 0,
+        movetime: 1000,
+        max_turns: 200,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{name} needs a value"));
+
+        match arg.as_str() {
+            "--games" => options.games = parse_number("--games", &value("--games")?)?,
+            "--seed" => options.seed = parse_number("--seed", &value("--seed")?)?,
+            "--movetime" => options.movetime = parse_number("--movetime", &value("--movetime")?)?,
+            "--max-turns" => {
+                options.max_turns = parse_number("--max-turns", &value("--max-turns")?)?;
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown option {arg}")),
+            _ => engines.push(arg),
+        }
+    }
+
+    options.engines = engines
+        .try_into()
+        .map_err(|_| "Exactly two engines are needed".to_owned())?;
+
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{name} needs a number, not {value}"))
+}
@@ -0,0 +1,312 @@
+//! A Convoy engine speaking a line-based protocol over stdin and stdout, so that programs written
+//! in any language can drive it.
+//!
+//! The driver sends one command per line and the engine answers with zero or more lines:
+//!
+//! | Command                                  | Answer                                          |
+//! |------------------------------------------|-------------------------------------------------|
+//! | `convoy`                                 | `id name <name>` then `convoyok`                |
+//! | `isready`                                | `readyok` once every earlier command is done    |
+//! | `map seed <n>`                           | Generates the map for following games           |
+//! | `map file <path>`                        | Loads a map file for following games            |
+//! | `newgame`                                | Starts a game on the current map                |
+//! | `position startpos [actions <a>...]`     | Starts a game on the current map then acts      |
+//! | `position string <fields> [actions <a>...]` | Sets up a position string then acts          |
+//! | `action <a>`                             | Takes an action, `result <r>` if the game ends  |
+//! | `go [movetime <ms>]`                     | `action <a>` for the current player, not taken  |
+//! | `state`                                  | `position <position string>`                    |
+//! | `hash`                                   | `hash <position hash in hex>`                   |
+//! | `quit`                                   | Exits                                           |
+//!
+//! Actions are written in action notation, results as `1-0`, `0-1` or `1/2-1/2`, and anything
+//! going wrong is answered with `error <message>`. A `position` command with an illegal action
+//! leaves the game as it was, and `movetime` only applies to the `go` it is given with.
+//!
+//! Usage: `engine [--agent random|alpha-beta|mcts] [--seed <n>]`
+
+use ai::{AlphaBeta, AlphaBetaAgent, Mcts};
+use convoy::{
+    Action, ActionOutcome, Game, Player,
+    agent::{Agent, RandomAgent},
+    board::Board,
+    generator::GeneratorSettings,
+    map::Map,
+    rules::RuleSet,
+};
+use std::{
+    env, fs,
+    io::{self, BufRead, Write},
+    process::ExitCode,
+    time::Duration,
+};
+
+enum EngineAgent {
+    Random(RandomAgent),
+    AlphaBeta(AlphaBetaAgent),
+    Mcts(Mcts),
+}
+
+impl EngineAgent {
+    fn new(name: &str, seed: u64) -> Option<Self> {
+        match name {
+            "random" => Some(Self::Random(RandomAgent::new(seed))),
+            "alpha-beta" => Some(Self::AlphaBeta(AlphaBetaAgent::new(AlphaBeta::default()))),
+            "mcts" => Some(Self::Mcts(Mcts::new(seed))),
+            _ => None,
+        }
+    }
+
+    fn agent(&mut self) -> &mut dyn Agent {
+        match self {
+            Self::Random(agent) => agent,
+            Self::AlphaBeta(agent) => agent,
+            Self::Mcts(agent) => agent,
+        }
+    }
+
+    /// Sets how long the agent searches, returning the previous iterations and time limits so
+    /// that they can be restored
+    const fn set_limits(
+        &mut self,
+        iterations: Option<u32>,
+        time_limit: Option<Duration>,
+    ) -> (Option<u32>, Option<Duration>) {
+        match self {
+            Self::Random(_) => (None, None),
+            Self::AlphaBeta(agent) => {
+                let previous = agent.search.time_limit;
+                agent.search.time_limit = time_limit;
+                (None, previous)
+            }
+            Self::Mcts(agent) => {
+                let previous = (agent.iterations, agent.time_limit);
+                agent.iterations = iterations;
+                agent.time_limit = time_limit;
+                previous
+            }
+        }
+    }
+}
+
+struct Engine {
+    agent: EngineAgent,
+    board: Board,
+    /// None once the game is over
+    game: Option<Box<Game>>,
+}
+
+impl Engine {
+    /// Handles one command, returning its answer lines and whether to quit
+    fn handle(&mut self, line: &str) -> Result<(Vec<String>, bool), String> {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok((Vec::new(), false));
+        };
+        let rest: Vec<&str> = words.collect();
+
+        let answer = match (command, &rest[..]) {
+            ("convoy", []) => vec![
+                format!("id name {}", self.agent.agent().name()),
+                "convoyok".to_owned(),
+            ],
+            ("isready", []) => vec!["readyok".to_owned()],
+            ("map", ["seed", seed]) => {
+                let seed = seed.parse().map_err(|_| format!("{seed} is not a seed"))?;
+                self.board = GeneratorSettings::default()
+                    .generate(seed)
+                    .map_err(|error| error.to_string())?;
+                Vec::new()
+            }
+            ("map", ["file", path]) => {
+                let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+                self.board = text
+                    .parse::<Map>()
+                    .map_err(|error| error.to_string())?
+                    .board;
+                Vec::new()
+            }
+            ("newgame", []) => {
+                self.game = Some(Game::with_board(self.board.clone(), RuleSet::default()));
+                Vec::new()
+            }
+            ("position", ["startpos", actions @ ..]) => {
+                let game = Game::with_board(self.board.clone(), RuleSet::default());
+                self.set_position(game, actions)?
+            }
+            ("position", ["string", rest @ ..]) if rest.len() >= 7 => {
+                let (fields, actions) = rest.split_at(7);
+                let game = Game::from_position_string(&fields.join(" "), RuleSet::default())
+                    .map_err(|error| error.to_string())?;
+                self.set_position(game, actions)?
+            }
+            ("action", [action]) => self.take_action(action)?.into_iter().collect(),
+            ("go", []) => vec![self.go()?],
+            ("go", ["movetime", time]) => {
+                let time = time.parse().map_err(|_| format!("{time} is not a time"))?;
+                vec![self.go_for(Duration::from_millis(time))?]
+            }
+            ("state", []) => vec![format!("position {}", self.game()?.to_position_string())],
+            ("hash", []) => vec![format!("hash {:016x}", self.game()?.position_hash())],
+            ("quit", []) => return Ok((Vec::new(), true)),
+            _ => return Err(format!("Unknown command {line}")),
+        };
+
+        Ok((answer, false))
+    }
+
+    fn game(&self) -> Result<&Game, String> {
+        self.game
+            .as_deref()
+            .ok_or_else(|| "There is no game in progress".to_owned())
+    }
+
+    fn go(&mut self) -> Result<String, String> {
+        let game = self
+            .game
+            .as_deref()
+            .ok_or_else(|| "There is no game in progress".to_owned())?;
+
+        Ok(format!("action {}", self.agent.agent().next_action(game)))
+    }
+
+    /// Searches for `time` instead of the agent's usual limits, only for this action
+    fn go_for(&mut self, time: Duration) -> Result<String, String> {
+        let (iterations, time_limit) = self.agent.set_limits(None, Some(time));
+        let answer = self.go();
+        self.agent.set_limits(iterations, time_limit);
+
+        answer
+    }
+
+    /// Sets up `game` then takes `actions` after `actions`, the keyword introducing them. The
+    /// current game is only replaced if every action is taken.
+    fn set_position(
+        &mut self,
+        mut game: Box<Game>,
+        actions: &[&str],
+    ) -> Result<Vec<String>, String> {
+        let actions = match actions {
+            [] => &[],
+            ["actions", actions @ ..] => actions,
+            [word, ..] => return Err(format!("Expected actions but found {word}")),
+        };
+
+        for (index, action) in actions.iter().enumerate() {
+            match act(game, action).map_err(|(_, error)| error)? {
+                Acted::Ongoing(next) => game = next,
+                Acted::Over(result) => {
+                    if let Some(next) = actions.get(index + 1) {
+                        return Err(format!("The game is over before {next}"));
+                    }
+
+                    self.game = None;
+                    return Ok(vec![result]);
+                }
+            }
+        }
+
+        self.game = Some(game);
+
+        Ok(Vec::new())
+    }
+
+    /// Takes `action`, returning the result if it ends the game
+    fn take_action(&mut self, action: &str) -> Result<Option<String>, String> {
+        let game = self
+            .game
+            .take()
+            .ok_or_else(|| "There is no game in progress".to_owned())?;
+
+        match act(game, action) {
+            Ok(Acted::Ongoing(next)) => {
+                self.game = Some(next);
+                Ok(None)
+            }
+            Ok(Acted::Over(result)) => Ok(Some(result)),
+            Err((game, error)) => {
+                self.game = Some(game);
+                Err(error)
+            }
+        }
+    }
+}
+
+/// What taking an action led to
+enum Acted {
+    Ongoing(Box<Game>),
+    /// The game ended, with its `result` line
+    Over(String),
+}
+
+/// Takes `action` in `game`, handing the game back unchanged if that fails
+fn act(game: Box<Game>, action: &str) -> Result<Acted, (Box<Game>, String)> {
+    let action: Action = match action.parse() {
+        Ok(action) => action,
+        Err(error) => return Err((game, format!("{error}"))),
+    };
+
+    let result = match game.take_action(action) {
+        Ok(ActionOutcome::Ongoing(next)) => return Ok(Acted::Ongoing(next)),
+        Ok(ActionOutcome::Draw) => "1/2-1/2",
+        Ok(ActionOutcome::Winner(Player::P1)) => "1-0",
+        Ok(ActionOutcome::Winner(Player::P2)) => "0-1",
+        Err((game, error)) => return Err((game, error.to_string())),
+    };
+
+    Ok(Acted::Over(format!("result {result}")))
+}
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let mut agent = "mcts".to_owned();
+    let mut seed = 0;
+
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--agent", Some(name)) => agent = name,
+            ("--seed", Some(value)) => {
+                let Ok(value) = value.parse() else {
+                    eprintln!("{value} is not a seed");
+                    return ExitCode::FAILURE;
+                };
+                seed = value;
+            }
+            _ => {
+                eprintln!("Usage: engine [--agent random|alpha-beta|mcts] [--seed <n>]");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let Some(agent) = EngineAgent::new(&agent, seed) else {
+        eprintln!("Unknown agent {agent}");
+        return ExitCode::FAILURE;
+    };
+    let mut engine = Engine {
+        agent,
+        board: Board::default(),
+        game: None,
+    };
+    let mut stdout = io::stdout().lock();
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let (answer, quit) = engine
+            .handle(&line)
+            .unwrap_or_else(|error| (vec![format!("error {error}")], false));
+
+        for answer in answer {
+            if writeln!(stdout, "{answer}").is_err() {
+                return ExitCode::FAILURE;
+            }
+        }
+
+        if stdout.flush().is_err() || quit {
+            break;
+        }
+    }
+
+    ExitCode::SUCCESS
+}
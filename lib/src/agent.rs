@@ -0,0 +1,38 @@
+//! Players driven by code rather than a person.
+
+use crate::{rng::Rng, Action, Game};
+
+/// Chooses actions for whichever player's turn it is
+pub trait Agent {
+    /// A short name for reports
+    fn name(&self) -> String;
+
+    /// The next action for the current player of `game`, which should be legal, such as one of
+    /// [`Game::candidate_actions`]
+    fn next_action(&mut self, game: &Game) -> Action;
+}
+
+/// Picks uniformly among the [`Game::candidate_actions`]
+#[derive(Clone, Debug)]
+pub struct RandomAgent {
+    rng: Rng,
+}
+
+impl RandomAgent {
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self { rng: Rng::new(seed) }
+    }
+}
+
+impl Agent for RandomAgent {
+    fn name(&self) -> String {
+        "random".to_owned()
+    }
+
+    fn next_action(&mut self, game: &Game) -> Action {
+        let actions = game.candidate_actions();
+
+        self.rng.choose(&actions).cloned().unwrap_or(Action::EndTurn)
+    }
+}
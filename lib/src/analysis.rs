@@ -0,0 +1,273 @@
+//! A breakdown of what a position means for each player, shared by the bots scoring positions and
+//! the desktop showing them to players.
+
+use crate::{coord::Coord, pieces::PieceType, tile::TileType, Game, Player};
+use std::ops::Index;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The state of the board from both players' side, see [`Game::analysis`]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Analysis {
+    players: [PlayerAnalysis; 2],
+}
+
+/// The strengths and weaknesses of one player
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlayerAnalysis {
+    /// The total cost of the player's pieces
+    pub material: u32,
+    /// The money the player is paid at their next upkeep
+    pub income: u8,
+    /// The player's pieces destroyed at their next resupply unless brought back into the
+    /// logistics network
+    pub unsupplied: Vec<Coord>,
+    /// The total cost of the pieces in `unsupplied`
+    pub unsupplied_material: u32,
+    /// The player's pieces within range of an enemy artillery
+    pub threatened: Vec<Coord>,
+    /// The total cost of the pieces in `threatened`
+    pub threatened_material: u32,
+    /// The enemy pieces able to reach the player's baseline in one move, empty unless occupying
+    /// it wins the game
+    pub baseline_threats: Vec<Coord>,
+}
+
+/// How much each component of an [`Analysis`] counts towards a [`Score`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Weights {
+    /// Per unit of cost of the pieces on the board
+    pub material: i32,
+    /// Per unit of income
+    pub income: i32,
+    /// Per unit of cost of the unsupplied pieces
+    pub unsupplied: i32,
+    /// Per unit of cost of the pieces within range of enemy artillery
+    pub threatened: i32,
+    /// Per piece able to reach the enemy baseline
+    pub baseline_threats: i32,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            material: 12,
+            income: 15,
+            unsupplied: 8,
+            threatened: 3,
+            baseline_threats: 40,
+        }
+    }
+}
+
+/// Weighted components of an [`Analysis`] from one player's side, each being how much better off
+/// the player is than their opponent
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Score {
+    /// From having more valuable pieces on the board
+    pub material: i32,
+    /// From being paid more at upkeep
+    pub income: i32,
+    /// From the opponent having more valuable pieces cut off from supplies
+    pub unsupplied: i32,
+    /// From the opponent having more valuable pieces within range of artillery
+    pub threatened: i32,
+    /// From having more pieces able to reach the enemy baseline
+    pub baseline_threats: i32,
+}
+
+impl Score {
+    /// The sum of every component, saturating rather than overflowing
+    #[must_use]
+    pub const fn total(&self) -> i32 {
+        self.material
+            .saturating_add(self.income)
+            .saturating_add(self.unsupplied)
+            .saturating_add(self.threatened)
+            .saturating_add(self.baseline_threats)
+    }
+}
+
+impl Game {
+    /// Analyses the position for both players.
+    ///
+    /// ```
+    /// use convoy::{analysis::Weights, Game, Player};
+    ///
+    /// let analysis = Game::new().analysis();
+    /// let weights = Weights::default();
+    ///
+    /// assert_eq!(analysis[Player::P1].income, analysis[Player::P2].income);
+    /// assert_eq!(
+    ///     analysis.score(Player::P1, &weights).total(),
+    ///     -analysis.score(Player::P2, &weights).total()
+    /// );
+    /// ```
+    #[must_use]
+    pub fn analysis(&self) -> Analysis {
+        Analysis {
+            players: [
+                self.analyse_player(Player::P1),
+                self.analyse_player(Player::P2),
+            ],
+        }
+    }
+
+    fn analyse_player(&self, player: Player) -> PlayerAnalysis {
+        let board = &self.board;
+        let cost = |coord: &Coord| {
+            board[*coord]
+                .piece_option
+                .map_or(0, |piece| u32::from(self.rules[piece.piece_type].cost))
+        };
+        let owned_by = |coord: &Coord, owner| {
+            board[*coord]
+                .piece_option
+                .is_some_and(|piece| piece.owner == owner)
+        };
+
+        let unsupplied: Vec<Coord> = board
+            .unsupplied_pieces(player)
+            .into_iter()
+            .filter(|&coord| {
+                board[coord]
+                    .piece_option
+                    .is_some_and(|piece| self.rules[piece.piece_type].needs_supplies)
+            })
+            .collect();
+
+        let artillery: Vec<Coord> = board
+            .coords()
+            .filter(|&coord| {
+                board[coord].piece_option.is_some_and(|piece| {
+                    piece.owner != player && piece.piece_type == PieceType::Artillery
+                })
+            })
+            .collect();
+        let threatened: Vec<Coord> = board
+            .coords()
+            .filter(|coord| {
+                owned_by(coord, player)
+                    && artillery
+                        .iter()
+                        .any(|&gun| gun.in_range(*coord, &self.rules.artillery.range))
+            })
+            .collect();
+
+        let baseline_threats = if self.rules.end_conditions.victory.baseline_occupation {
+            board
+                .coords()
+                .filter(|coord| {
+                    owned_by(coord, player.opponent())
+                        && board
+                            .get_moves(*coord, &self.rules)
+                            .unwrap_or_default()
+                            .into_iter()
+                            .any(|to| board[to].tile_type == TileType::Baseline(player))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        PlayerAnalysis {
+            material: board
+                .coords()
+                .filter(|coord| owned_by(coord, player))
+                .map(|coord| cost(&coord))
+                .sum(),
+            income: self.income(player),
+            unsupplied_material: unsupplied.iter().map(cost).sum(),
+            unsupplied,
+            threatened_material: threatened.iter().map(cost).sum(),
+            threatened,
+            baseline_threats,
+        }
+    }
+}
+
+impl Analysis {
+    /// Scores the position for `player`, positive when it favours them. Each component
+    /// saturates rather than overflowing with extreme weights or costs.
+    ///
+    /// ```
+    /// use convoy::{analysis::Weights, coord::Coord, rules::RuleSet, Game, Player};
+    ///
+    /// // P1's infantry on c1 is cut off from supplies and under fire from P2's artillery on f1,
+    /// // while P2's recon on c0 can reach P1's baseline
+    /// let position = "3B/2.T/3./3./3./3b 2C/3/rI1/3/3/1a1 1 C 3,3 0 0";
+    /// let game = Game::from_position_string(position, RuleSet::default()).unwrap();
+    /// let analysis = game.analysis();
+    /// let coord = |coord: &str| coord.parse::<Coord>().unwrap();
+    ///
+    /// let p1 = &analysis[Player::P1];
+    /// assert_eq!((p1.material, p1.income), (5, 3));
+    /// assert_eq!((p1.unsupplied.clone(), p1.unsupplied_material), (vec![coord("c1")], 2));
+    /// assert_eq!((p1.threatened.clone(), p1.threatened_material), (vec![coord("c1")], 2));
+    /// assert_eq!(p1.baseline_threats, [coord("c0")]);
+    ///
+    /// // The recon needs no supplies and P1 has no artillery
+    /// let p2 = &analysis[Player::P2];
+    /// assert_eq!((p2.material, p2.income), (8, 2));
+    /// assert!(p2.unsupplied.is_empty() && p2.threatened.is_empty());
+    /// assert!(p2.baseline_threats.is_empty());
+    ///
+    /// let score = analysis.score(Player::P1, &Weights::default());
+    /// assert_eq!(score.material, 12 * (5 - 8));
+    /// assert_eq!(score.income, 15 * (3 - 2));
+    /// assert_eq!(score.unsupplied, 8 * (0 - 2));
+    /// assert_eq!(score.threatened, 3 * (0 - 2));
+    /// assert_eq!(score.baseline_threats, 40 * (0 - 1));
+    /// assert_eq!(score.total(), -83);
+    /// assert_eq!(analysis.score(Player::P2, &Weights::default()).total(), 83);
+    ///
+    /// let extreme = Weights { material: i32::MAX, ..Weights::default() };
+    /// assert_eq!(analysis.score(Player::P2, &extreme).material, i32::MAX);
+    /// assert_eq!(analysis.score(Player::P2, &extreme).total(), i32::MAX);
+    /// ```
+    #[must_use]
+    pub fn score(&self, player: Player, weights: &Weights) -> Score {
+        let (own, other) = (&self[player], &self[player.opponent()]);
+        let weigh = |weight: i32, own: u32, other: u32| {
+            let signed = |value| i32::try_from(value).unwrap_or(i32::MAX);
+            weight.saturating_mul(signed(own).saturating_sub(signed(other)))
+        };
+        let count = |coords: &[Coord]| u32::try_from(coords.len()).unwrap_or(u32::MAX);
+
+        Score {
+            material: weigh(weights.material, own.material, other.material),
+            income: weigh(weights.income, own.income.into(), other.income.into()),
+            unsupplied: weigh(
+                weights.unsupplied,
+                other.unsupplied_material,
+                own.unsupplied_material,
+            ),
+            threatened: weigh(
+                weights.threatened,
+                other.threatened_material,
+                own.threatened_material,
+            ),
+            baseline_threats: weigh(
+                weights.baseline_threats,
+                count(&other.baseline_threats),
+                count(&own.baseline_threats),
+            ),
+        }
+    }
+}
+
+impl Index<Player> for Analysis {
+    type Output = PlayerAnalysis;
+
+    fn index(&self, index: Player) -> &Self::Output {
+        match index {
+            Player::P1 => &self.players[0],
+            Player::P2 => &self.players[1],
+        }
+    }
+}
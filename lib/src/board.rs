@@ -1,8 +1,12 @@
+use std::collections::{HashSet, VecDeque};
 use std::ops::{Index, IndexMut};
-use std::{array, slice};
+use std::slice;
 use crate::{
-    constants::{BOARD_FILES, BOARD_RANKS},
-    coord::Coord,
+    constants::{DEFAULT_BOARD_FILES, DEFAULT_BOARD_RANKS},
+    coord::{Coord, Move},
+    errors::ParseCoordError,
+    pieces::PieceType,
+    rules::RuleSet,
     tile::{Tile, TileType},
     Player,
 };
@@ -10,10 +14,13 @@ use crate::{
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Board {
-    tiles: [[Tile; BOARD_FILES as usize]; BOARD_RANKS as usize],
+    ranks: u8,
+    files: u8,
+    /// Stored rank by rank
+    tiles: Vec<Tile>,
 }
 
 impl Board {
@@ -22,93 +29,289 @@ impl Board {
         Self::default()
     }
 
+    /// An empty board with each player's baseline along the first and last rank respectively
+    ///
+    /// Returns None if there is not enough room for both baselines
     #[must_use]
-    pub fn get_moves(&self, piece: Coord) -> Option<Vec<Coord>> {
-        let _piece = self[piece].piece_option?;
+    pub fn with_size(ranks: u8, files: u8) -> Option<Self> {
+        if ranks < 2 || files == 0 {
+            return None;
+        }
 
-        Some(
-            [
-                Coord::new(piece.rank + 1, piece.file),
-                Coord::new(piece.rank.wrapping_sub(1), piece.file),
-                Coord::new(piece.rank, piece.file + 1),
-                Coord::new(piece.rank, piece.file.wrapping_sub(1)),
-            ]
-            .into_iter()
-            .filter_map(|coord| {
-                coord.and_then(|coord| {
-                    if self[coord].piece_option.is_some() {
-                        None
-                    } else {
-                        Some(coord)
-                    }
+        let tiles = (0..ranks)
+            .flat_map(|rank| {
+                let tile_type = if rank == 0 {
+                    TileType::Baseline(Player::P1)
+                } else if rank == ranks - 1 {
+                    TileType::Baseline(Player::P2)
+                } else {
+                    TileType::Regular
+                };
+
+                (0..files).map(move |_| Tile {
+                    piece_option: None,
+                    tile_type,
                 })
             })
-            .collect(),
+            .collect();
+
+        Some(Self {
+            ranks,
+            files,
+            tiles,
+        })
+    }
+
+    #[must_use]
+    pub const fn ranks(&self) -> u8 {
+        self.ranks
+    }
+
+    #[must_use]
+    pub const fn files(&self) -> u8 {
+        self.files
+    }
+
+    #[must_use]
+    pub const fn contains(&self, Coord { rank, file }: Coord) -> bool {
+        rank < self.ranks && file < self.files
+    }
+
+    /// The tile on `coord`, if it is on the board
+    #[must_use]
+    pub fn get(&self, coord: Coord) -> Option<&Tile> {
+        self.contains(coord).then(|| &self.tiles[self.tile_index(coord)])
+    }
+
+    /// The coord at `rank` and `file`, if it is on the board
+    #[must_use]
+    pub fn coord(&self, rank: impl TryInto<u8>, file: impl TryInto<u8>) -> Option<Coord> {
+        Coord::new(rank, file).filter(|&coord| self.contains(coord))
+    }
+
+    /// Parses a coord such as `a3`, checking that it is on the board
+    ///
+    /// # Errors
+    ///
+    /// Will return Err if `s` is not a coord or is off the board
+    pub fn parse_coord(&self, s: &str) -> Result<Coord, ParseCoordError> {
+        let coord: Coord = s.parse()?;
+
+        if self.contains(coord) {
+            Ok(coord)
+        } else {
+            Err(ParseCoordError::OffBoard(coord))
+        }
+    }
+
+    /// Every coord the piece on `piece` can reach within its speed, moving orthogonally and never
+    /// through another piece
+    #[must_use]
+    pub fn get_moves(&self, piece: Coord, rules: &RuleSet) -> Option<Vec<Coord>> {
+        Some(
+            self.reachable(piece, rules)?
+                .into_iter()
+                .map(|(coord, _)| coord)
+                .collect(),
         )
     }
 
+    /// The shortest path taken by the piece moving along `move`, ending with `move.to` and
+    /// excluding `move.from`
+    ///
+    /// ```
+    /// use convoy::{coord::Coord, rules::RuleSet, Game};
+    ///
+    /// // A recon, moving up to 4 tiles, with an infantry in front of it
+    /// let position = "3B/3./3./3b R2/I2/3/2i 1 C 3,3 0 0";
+    /// let game = Game::from_position_string(position, RuleSet::default()).unwrap();
+    /// let (board, rules) = (game.board(), game.rules());
+    /// let coords = |coords: &[&str]| -> Vec<Coord> {
+    ///     coords.iter().map(|coord| coord.parse().unwrap()).collect()
+    /// };
+    ///
+    /// // The path goes around the infantry
+    /// assert_eq!(
+    ///     board.get_path("a0-c0".parse().unwrap(), rules),
+    ///     Some(coords(&["a1", "b1", "c1", "c0"]))
+    /// );
+    ///
+    /// // Going around to d0 would take 5 tiles, and occupied tiles cannot be reached at all
+    /// assert_eq!(board.get_path("a0-d0".parse().unwrap(), rules), None);
+    /// assert_eq!(board.get_path("a0-d2".parse().unwrap(), rules), None);
+    /// assert_eq!(board.get_path("a0-b0".parse().unwrap(), rules), None);
+    ///
+    /// // The infantry moves up to 2 tiles, never onto the recon
+    /// let mut moves = board.get_moves("b0".parse().unwrap(), rules).unwrap();
+    /// moves.sort_by_key(|coord| (coord.rank, coord.file));
+    /// assert_eq!(moves, coords(&["a1", "b1", "b2", "c0", "c1", "d0"]));
+    /// ```
+    #[must_use]
+    pub fn get_path(&self, Move { from, to }: Move, rules: &RuleSet) -> Option<Vec<Coord>> {
+        let reachable = self.reachable(from, rules)?;
+        let previous = |coord| {
+            reachable
+                .iter()
+                .find_map(|&(reached, previous)| (reached == coord).then_some(previous))
+        };
+
+        let mut path = vec![to];
+        let mut cur = previous(to)?;
+
+        while cur != from {
+            path.push(cur);
+            cur = previous(cur)?;
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    /// Breadth-first search of the coords reachable by the piece on `from`, each paired with the
+    /// coord it was reached from
+    fn reachable(&self, from: Coord, rules: &RuleSet) -> Option<Vec<(Coord, Coord)>> {
+        let speed = rules[self.get(from)?.piece_option?.piece_type].speed;
+        let mut reachable = Vec::new();
+        let mut visited = HashSet::from([from]);
+        let mut queue = VecDeque::from([(from, 0)]);
+
+        while let Some((coord, distance)) = queue.pop_front() {
+            if distance == speed {
+                continue;
+            }
+
+            for neighbour in self.neighbour_coords(coord) {
+                if self[neighbour].piece_option.is_none() && visited.insert(neighbour) {
+                    reachable.push((neighbour, coord));
+                    queue.push_back((neighbour, distance + 1));
+                }
+            }
+        }
+
+        Some(reachable)
+    }
+
     /// Neighbours are orthogonal only
     #[must_use]
-    pub fn get_neighbours(&self, Coord { rank, file }: Coord) -> Vec<Tile> {
+    pub fn get_neighbours(&self, coord: Coord) -> Vec<Tile> {
+        self.neighbour_coords(coord)
+            .map(|coord| self[coord])
+            .collect()
+    }
+
+    /// Every coord covered by `player`'s logistics network.
+    ///
+    /// The network is rooted in `player`'s baseline and is carried further by their convoys and
+    /// towns standing on or next to it. A coord is covered when it is one of those roots or hubs,
+    /// or neighbours one.
+    #[must_use]
+    pub fn logistics_network(&self, player: Player) -> HashSet<Coord> {
+        let mut hubs: HashSet<Coord> = self
+            .coords()
+            .filter(|&coord| self[coord].produces_troops(player))
+            .collect();
+        let mut queue: VecDeque<Coord> = hubs.iter().copied().collect();
+        let mut network = HashSet::new();
+
+        while let Some(hub) = queue.pop_front() {
+            network.insert(hub);
+
+            for neighbour in self.neighbour_coords(hub) {
+                network.insert(neighbour);
+
+                let is_hub = self[neighbour].has_supplies(player)
+                    || self[neighbour].piece_option.is_some_and(|piece| {
+                        piece.owner == player && piece.piece_type == PieceType::Convoy
+                    });
+
+                if is_hub && hubs.insert(neighbour) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        network
+    }
+
+    /// The coords of `player`'s pieces outside of their logistics network
+    #[must_use]
+    pub fn unsupplied_pieces(&self, player: Player) -> Vec<Coord> {
+        let network = self.logistics_network(player);
+
+        self.coords()
+            .filter(|coord| {
+                !network.contains(coord)
+                    && self[*coord]
+                        .piece_option
+                        .is_some_and(|piece| piece.owner == player)
+            })
+            .collect()
+    }
+
+    #[must_use]
+    pub fn towns_owned_by(&self, player: Player) -> Vec<Coord> {
+        self.coords()
+            .filter(|&coord| self[coord].tile_type == TileType::Town(Some(player)))
+            .collect()
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord> + use<> {
+        let files = self.files;
+
+        (0..self.ranks).flat_map(move |rank| (0..files).map(move |file| Coord { rank, file }))
+    }
+
+    fn neighbour_coords(&self, Coord { rank, file }: Coord) -> impl Iterator<Item = Coord> {
         [
-            Coord::new(rank + 1, file),
-            Coord::new(rank.wrapping_sub(1), file),
-            Coord::new(rank, file + 1),
-            Coord::new(rank, file.wrapping_sub(1)),
+            rank.checked_add(1).map(|rank| Coord { rank, file }),
+            rank.checked_sub(1).map(|rank| Coord { rank, file }),
+            file.checked_add(1).map(|file| Coord { rank, file }),
+            file.checked_sub(1).map(|file| Coord { rank, file }),
         ]
         .into_iter()
-        .filter_map(|coord| coord.map(|coord| self[coord]))
-        .collect()
+        .flatten()
+        .filter(|&coord| self.contains(coord))
+    }
+
+    fn tile_index(&self, coord: Coord) -> usize {
+        assert!(self.contains(coord), "{coord} is not on the board");
+
+        usize::from(coord.rank) * usize::from(self.files) + usize::from(coord.file)
     }
 
-    pub fn iter(&self) -> slice::Iter<Tile> {
+    pub fn iter(&self) -> slice::Iter<'_, Tile> {
         self.into_iter()
     }
 
-    pub fn iter_mut(&mut self) -> slice::IterMut<Tile> {
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, Tile> {
         self.into_iter()
     }
 
     pub fn rows(&self) -> impl Iterator<Item = &[Tile]> {
-        self.tiles
-            .iter()
-            .map(<[Tile; BOARD_FILES as usize]>::as_slice)
+        self.tiles.chunks(usize::from(self.files))
     }
 }
 
 impl Default for Board {
     fn default() -> Self {
-        Self {
-            tiles: array::from_fn(|rank| {
-                if rank == 0 {
-                    array::from_fn(|_| Tile {
-                        piece_option: None,
-                        tile_type: TileType::Baseline(Player::P1),
-                    })
-                } else if rank == (BOARD_RANKS - 1) as usize {
-                    array::from_fn(|_| Tile {
-                        piece_option: None,
-                        tile_type: TileType::Baseline(Player::P2),
-                    })
-                } else {
-                    array::from_fn(|_| Tile::default())
-                }
-            }),
-        }
+        Self::with_size(DEFAULT_BOARD_RANKS, DEFAULT_BOARD_FILES)
+            .expect("The default size has room for both baselines")
     }
 }
 
 impl Index<Coord> for Board {
     type Output = Tile;
 
-    fn index(&self, Coord { rank, file }: Coord) -> &Self::Output {
-        &self.tiles[rank as usize][file as usize]
+    fn index(&self, coord: Coord) -> &Self::Output {
+        &self.tiles[self.tile_index(coord)]
     }
 }
 
 impl IndexMut<Coord> for Board {
-    fn index_mut(&mut self, Coord { rank, file }: Coord) -> &mut Self::Output {
-        &mut self.tiles[rank as usize][file as usize]
+    fn index_mut(&mut self, coord: Coord) -> &mut Self::Output {
+        let index = self.tile_index(coord);
+
+        &mut self.tiles[index]
     }
 }
 
@@ -117,7 +320,7 @@ impl<'a> IntoIterator for &'a Board {
     type IntoIter = slice::Iter<'a, Tile>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.tiles.as_flattened().iter()
+        self.tiles.iter()
     }
 }
 
@@ -126,6 +329,6 @@ impl<'a> IntoIterator for &'a mut Board {
     type IntoIter = slice::IterMut<'a, Tile>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.tiles.as_flattened_mut().iter_mut()
+        self.tiles.iter_mut()
     }
 }
@@ -1,3 +1,2 @@
-pub const BOARD_FILES: u8 = 14;
-pub const BOARD_RANKS: u8 = 21;
-pub const RANK_LETTERS: &str = "abcdefghijklmnopqrstu";
+pub const DEFAULT_BOARD_FILES: u8 = 14;
+pub const DEFAULT_BOARD_RANKS: u8 = 21;
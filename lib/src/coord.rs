@@ -1,10 +1,14 @@
-use crate::constants::{BOARD_FILES, BOARD_RANKS, RANK_LETTERS};
-use std::fmt::{Display, Formatter};
+use crate::errors::{ParseCoordError, ParseMoveError};
+use std::{
+    fmt::{Display, Formatter},
+    ops::RangeInclusive,
+    str::FromStr,
+};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Coord {
     pub rank: u8,
@@ -12,35 +16,122 @@ pub struct Coord {
 }
 
 impl Coord {
+    /// Use [`Board::coord`](crate::board::Board::coord) to also check that the coord is on the
+    /// board
     #[must_use]
     pub fn new(rank: impl TryInto<u8>, file: impl TryInto<u8>) -> Option<Self> {
         let rank = rank.try_into().ok()?;
         let file = file.try_into().ok()?;
-        
-        if rank > BOARD_RANKS || file > BOARD_FILES {
-            return None;
-        }
 
         Some(Self { rank, file })
     }
 
+    /// The number of orthogonal steps to `other`, which can exceed a `u8` on the largest boards
+    ///
+    /// ```
+    /// use convoy::{board::Board, coord::Coord, rules::RuleSet, Game, Player};
+    ///
+    /// let corner = Coord { rank: 0, file: 0 };
+    /// let opposite = Coord { rank: u8::MAX - 1, file: u8::MAX - 1 };
+    ///
+    /// assert_eq!(corner.distance(opposite), 508);
+    /// assert!(!corner.in_range(opposite, &(2..=4)));
+    ///
+    /// // An artillery in one corner of the largest board and an infantry in the other
+    /// let board = Board::with_size(u8::MAX, u8::MAX).expect("The size is valid");
+    /// let empty = Game::with_board(board, RuleSet::default()).to_position_string();
+    /// let mut fields: Vec<&str> = empty.split(' ').collect();
+    /// let pieces = ["a254".to_owned()]
+    ///     .into_iter()
+    ///     .chain(vec!["255".to_owned(); 253])
+    ///     .chain(["254I".to_owned()])
+    ///     .collect::<Vec<_>>()
+    ///     .join("/");
+    /// fields[1] = &pieces;
+    ///
+    /// let game = Game::from_position_string(&fields.join(" "), RuleSet::default()).unwrap();
+    /// assert!(game.analysis()[Player::P1].threatened.is_empty());
+    /// ```
+    #[must_use]
+    pub const fn distance(&self, other: Self) -> u16 {
+        self.rank.abs_diff(other.rank) as u16 + self.file.abs_diff(other.file) as u16
+    }
+
+    /// Whether `other` is a distance within `range` away
     #[must_use]
-    pub const fn distance(&self, other: Self) -> u8 {
-        self.rank.abs_diff(other.rank) + self.file.abs_diff(other.file)
+    pub fn in_range(&self, other: Self, range: &RangeInclusive<u8>) -> bool {
+        u8::try_from(self.distance(other)).is_ok_and(|distance| range.contains(&distance))
     }
 }
 
+/// Ranks are lettered a to z, then aa to az and so on
+///
+/// ```
+/// use convoy::{board::Board, coord::Coord};
+///
+/// let board = Board::with_size(u8::MAX, u8::MAX).expect("The size is valid");
+///
+/// for coord in board.coords() {
+///     assert_eq!(coord.to_string().parse::<Coord>(), Ok(coord));
+/// }
+///
+/// // Only the written form of a coord parses, so parsing and writing are inverses
+/// assert!("a03".parse::<Coord>().is_err());
+/// assert!("a00".parse::<Coord>().is_err());
+/// assert_eq!("a0".parse::<Coord>(), Ok(Coord { rank: 0, file: 0 }));
+/// ```
 impl Display for Coord {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}{}",
-            RANK_LETTERS
-                .chars()
-                .nth(self.rank as usize)
-                .expect("Is within bounds"),
-            self.file
-        )
+        let mut letters = Vec::new();
+        let mut rank = u16::from(self.rank) + 1;
+
+        while rank > 0 {
+            rank -= 1;
+            letters.push(char::from(b'a' + u8::try_from(rank % 26).expect("Is below 26")));
+            rank /= 26;
+        }
+
+        write!(f, "{}{}", letters.iter().rev().collect::<String>(), self.file)
+    }
+}
+
+/// Parses the rank letters followed by the file number, such as `a3` or `aa12`. Use
+/// [`Board::parse_coord`](crate::board::Board::parse_coord) to also check that the coord is on the
+/// board.
+impl FromStr for Coord {
+    type Err = ParseCoordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.find(|c: char| !c.is_ascii_lowercase()).unwrap_or(s.len());
+        let (letters, number) = s.split_at(digits);
+
+        if letters.is_empty() {
+            return Err(ParseCoordError::MissingRank);
+        }
+
+        if number.is_empty() {
+            return Err(ParseCoordError::MissingFile);
+        }
+
+        if let Some(c) = number.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(ParseCoordError::UnexpectedCharacter(c));
+        }
+
+        if number.len() > 1 && number.starts_with('0') {
+            return Err(ParseCoordError::LeadingZero);
+        }
+
+        let rank = letters
+            .bytes()
+            .try_fold(0_u16, |rank, letter| {
+                rank.checked_mul(26)?
+                    .checked_add(u16::from(letter - b'a') + 1)
+            })
+            .and_then(|rank| u8::try_from(rank - 1).ok())
+            .ok_or(ParseCoordError::RankTooLarge)?;
+        let file = number.parse().map_err(|_| ParseCoordError::FileTooLarge)?;
+
+        Ok(Self { rank, file })
     }
 }
 
@@ -50,3 +141,41 @@ pub struct Move {
     pub from: Coord,
     pub to: Coord,
 }
+
+/// Written as the two coords joined by a dash, such as `b3-d3`
+///
+/// ```
+/// use convoy::{board::Board, coord::{Coord, Move}};
+///
+/// let board = Board::with_size(u8::MAX, u8::MAX).expect("The size is valid");
+///
+/// // Every square appears at both ends of a move
+/// for from in board.coords() {
+///     let to = Coord {
+///         rank: from.file,
+///         file: from.rank,
+///     };
+///
+///     for r#move in [Move { from, to }, Move { from: to, to: from }] {
+///         assert_eq!(r#move.to_string().parse::<Move>(), Ok(r#move));
+///     }
+/// }
+/// ```
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.from, self.to)
+    }
+}
+
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (from, to) = s.split_once('-').ok_or(ParseMoveError::MissingSeparator)?;
+
+        Ok(Self {
+            from: from.parse()?,
+            to: to.parse()?,
+        })
+    }
+}
@@ -0,0 +1,411 @@
+//! A compact binary encoding of games and actions.
+//!
+//! Every encoding starts with [`VERSION`], and decoding rejects any other version. Numbers are
+//! written as LEB128 varints and each tile takes a single byte, its type in the top 3 bits and its
+//! piece in the bottom 5.
+
+use crate::{
+    board::Board,
+    coord::{Coord, Move},
+    errors::DecodeError,
+    pieces::{Piece, PieceType},
+    rules::RuleSet,
+    tile::{Tile, TileType},
+    Action, AttackCommand, Command, DefenseCommand, Game, Phase, Player,
+};
+
+/// The version of the encoding written by this crate
+pub const VERSION: u8 = 1;
+
+impl Game {
+    /// The position encoded as bytes. Like [`Game::to_position_string`], the history and the
+    /// rules are not included.
+    ///
+    /// ```
+    /// use convoy::{rules::RuleSet, Game};
+    ///
+    /// let game = Game::new();
+    /// let bytes = game.to_bytes();
+    ///
+    /// assert_eq!(bytes.len(), 21 * 14 + 8);
+    /// assert_eq!(Game::from_bytes(&bytes, RuleSet::default()), Ok(game));
+    /// ```
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![VERSION, self.board.ranks(), self.board.files()];
+
+        bytes.extend(self.board.iter().map(|&tile| encode_tile(tile)));
+        bytes.push(
+            match self.cur_player {
+                Player::P1 => 0,
+                Player::P2 => 1,
+            } | match self.phase {
+                Phase::Upkeep => 0,
+                Phase::Command => 2,
+                Phase::Resupply => 4,
+            },
+        );
+
+        for money in self.player_money {
+            write_varint(&mut bytes, money.into());
+        }
+
+        write_varint(&mut bytes, self.turn.into());
+        write_varint(&mut bytes, self.turns_without_progress.into());
+
+        bytes
+    }
+
+    /// The inverse of [`Game::to_bytes`], played with `rules` and with an empty history
+    ///
+    /// # Errors
+    ///
+    /// Will return Err if `bytes` is not an encoded game of the current version
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use convoy::{rules::RuleSet, DecodeError, Game};
+    ///
+    /// // Replaces the last number, the turns without progress, with `varint`
+    /// let decode = |varint: &[u8]| {
+    ///     let mut bytes = Game::new().to_bytes();
+    ///     bytes.pop();
+    ///     bytes.extend(varint);
+    ///     Game::from_bytes(&bytes, RuleSet::default()).map(|game| game.to_position_string())
+    /// };
+    ///
+    /// assert!(decode(&[0x80, 0x00]).is_ok());
+    /// assert_eq!(decode(&[0x80]), Err(DecodeError::UnexpectedEnd));
+    /// assert_eq!(decode(&[0x80, 0x80, 0x04]), Err(DecodeError::NumberTooLarge(0x1_0000)));
+    ///
+    /// // Bits beyond the 64th are not dropped, even when what is left would fit
+    /// let mut oversized = [0x80; 10];
+    /// oversized[9] = 0x02;
+    /// assert_eq!(decode(&oversized), Err(DecodeError::NumberTooLarge(0)));
+    /// assert_eq!(decode(&[0x80; 11]), Err(DecodeError::NumberTooLarge(0)));
+    /// ```
+    pub fn from_bytes(bytes: &[u8], rules: RuleSet) -> Result<Box<Self>, DecodeError> {
+        let mut reader = Reader::new(bytes)?;
+        let ranks = reader.byte()?;
+        let files = reader.byte()?;
+        let mut board = Board::with_size(ranks, files).ok_or(DecodeError::BadSize)?;
+
+        for coord in board.coords() {
+            board[coord] = decode_tile(reader.byte()?)?;
+        }
+
+        let mut game = Self::with_board(board, rules);
+        let state = reader.byte()?;
+
+        game.cur_player = if state & 1 == 0 {
+            Player::P1
+        } else {
+            Player::P2
+        };
+        game.phase = match state >> 1 {
+            0 => Phase::Upkeep,
+            1 => Phase::Command,
+            2 => Phase::Resupply,
+            _ => return Err(DecodeError::InvalidByte(state)),
+        };
+        game.player_money = [reader.varint()?, reader.varint()?];
+        game.turn = reader.varint()?;
+        game.turns_without_progress = reader.varint()?;
+        reader.finish()?;
+
+        game.start();
+
+        Ok(game)
+    }
+}
+
+impl Action {
+    /// The action encoded as bytes
+    ///
+    /// ```
+    /// use convoy::Action;
+    ///
+    /// let action: Action = "c4,b3-b4>a4/c5,a4-a3".parse().expect("The notation is valid");
+    ///
+    /// assert_eq!(Action::from_bytes(&action.to_bytes()), Ok(action));
+    /// ```
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![VERSION];
+
+        match self {
+            Self::EndTurn => bytes.push(0),
+            Self::EndPhase => bytes.push(1),
+            Self::Command(Command::Move(r#move)) => {
+                bytes.push(2);
+                write_move(&mut bytes, *r#move);
+            }
+            Self::Command(Command::Purchase(piece_type, coord)) => {
+                bytes.push(3);
+                bytes.push(piece_type_index(*piece_type));
+                write_coord(&mut bytes, *coord);
+            }
+            Self::Command(Command::Battle {
+                attack_commands,
+                defense_commands,
+                target,
+            }) => {
+                bytes.push(4);
+                write_coord(&mut bytes, *target);
+                write_varint(&mut bytes, attack_commands.len() as u64);
+
+                for attack_command in attack_commands {
+                    match attack_command {
+                        AttackCommand::Attack(coord) => {
+                            bytes.push(0);
+                            write_coord(&mut bytes, *coord);
+                        }
+                        AttackCommand::MoveAttack(r#move) => {
+                            bytes.push(1);
+                            write_move(&mut bytes, *r#move);
+                        }
+                    }
+                }
+
+                write_varint(&mut bytes, defense_commands.len() as u64);
+
+                for defense_command in defense_commands {
+                    match defense_command {
+                        DefenseCommand::Defend(coord) => {
+                            bytes.push(0);
+                            write_coord(&mut bytes, *coord);
+                        }
+                        DefenseCommand::Retreat(r#move) => {
+                            bytes.push(1);
+                            write_move(&mut bytes, *r#move);
+                        }
+                    }
+                }
+            }
+        }
+
+        bytes
+    }
+
+    /// The inverse of [`Action::to_bytes`]
+    ///
+    /// # Errors
+    ///
+    /// Will return Err if `bytes` is not an encoded action of the current version
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader::new(bytes)?;
+
+        let action = match reader.byte()? {
+            0 => Self::EndTurn,
+            1 => Self::EndPhase,
+            2 => Self::Command(Command::Move(reader.r#move()?)),
+            3 => {
+                let piece_type = decode_piece_type(reader.byte()?)?;
+
+                Self::Command(Command::Purchase(piece_type, reader.coord()?))
+            }
+            4 => {
+                let target = reader.coord()?;
+                let attack_commands = reader.list(|reader| {
+                    Ok(match reader.byte()? {
+                        0 => AttackCommand::Attack(reader.coord()?),
+                        1 => AttackCommand::MoveAttack(reader.r#move()?),
+                        byte => return Err(DecodeError::InvalidByte(byte)),
+                    })
+                })?;
+                let defense_commands = reader.list(|reader| {
+                    Ok(match reader.byte()? {
+                        0 => DefenseCommand::Defend(reader.coord()?),
+                        1 => DefenseCommand::Retreat(reader.r#move()?),
+                        byte => return Err(DecodeError::InvalidByte(byte)),
+                    })
+                })?;
+
+                Self::Command(Command::Battle {
+                    attack_commands,
+                    defense_commands,
+                    target,
+                })
+            }
+            byte => return Err(DecodeError::InvalidByte(byte)),
+        };
+
+        reader.finish()?;
+
+        Ok(action)
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Starts reading after checking the version
+    fn new(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let mut reader = Self { bytes };
+
+        match reader.byte()? {
+            VERSION => Ok(reader),
+            version => Err(DecodeError::UnsupportedVersion(version)),
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        let (&byte, rest) = self.bytes.split_first().ok_or(DecodeError::UnexpectedEnd)?;
+        self.bytes = rest;
+
+        Ok(byte)
+    }
+
+    fn varint<T: TryFrom<u64>>(&mut self) -> Result<T, DecodeError> {
+        let mut value = 0_u64;
+
+        for shift in (0..u64::BITS).step_by(7) {
+            let byte = self.byte()?;
+            let bits = u64::from(byte & 0x7f);
+
+            // The last byte only has room for the top bit
+            if (bits << shift) >> shift != bits {
+                return Err(DecodeError::NumberTooLarge(value));
+            }
+
+            value |= bits << shift;
+
+            if byte & 0x80 == 0 {
+                return T::try_from(value).map_err(|_| DecodeError::NumberTooLarge(value));
+            }
+        }
+
+        Err(DecodeError::NumberTooLarge(value))
+    }
+
+    fn coord(&mut self) -> Result<Coord, DecodeError> {
+        Ok(Coord {
+            rank: self.byte()?,
+            file: self.byte()?,
+        })
+    }
+
+    fn r#move(&mut self) -> Result<Move, DecodeError> {
+        Ok(Move {
+            from: self.coord()?,
+            to: self.coord()?,
+        })
+    }
+
+    fn list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, DecodeError>,
+    ) -> Result<Vec<T>, DecodeError> {
+        let length: usize = self.varint()?;
+
+        // Each item takes at least one byte, which bounds the allocation by the input's length
+        if length > self.bytes.len() {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+
+        (0..length).map(|_| item(self)).collect()
+    }
+
+    const fn finish(self) -> Result<(), DecodeError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(DecodeError::TrailingBytes(self.bytes.len()))
+        }
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+
+    bytes.push(u8::try_from(value).expect("Is below 0x80"));
+}
+
+fn write_coord(bytes: &mut Vec<u8>, Coord { rank, file }: Coord) {
+    bytes.extend([rank, file]);
+}
+
+fn write_move(bytes: &mut Vec<u8>, Move { from, to }: Move) {
+    write_coord(bytes, from);
+    write_coord(bytes, to);
+}
+
+const fn encode_tile(tile: Tile) -> u8 {
+    let tile_type = match tile.tile_type {
+        TileType::Regular => 0,
+        TileType::Town(None) => 1,
+        TileType::Town(Some(Player::P1)) => 2,
+        TileType::Town(Some(Player::P2)) => 3,
+        TileType::Baseline(Player::P1) => 4,
+        TileType::Baseline(Player::P2) => 5,
+    };
+    let piece = match tile.piece_option {
+        None => 0,
+        Some(piece) => {
+            let owner = match piece.owner {
+                Player::P1 => 0,
+                Player::P2 => 8,
+            };
+
+            1 + owner + (piece_type_index(piece.piece_type) << 1) + piece.exhausted as u8
+        }
+    };
+
+    (tile_type << 5) | piece
+}
+
+fn decode_tile(byte: u8) -> Result<Tile, DecodeError> {
+    let tile_type = match byte >> 5 {
+        0 => TileType::Regular,
+        1 => TileType::Town(None),
+        2 => TileType::Town(Some(Player::P1)),
+        3 => TileType::Town(Some(Player::P2)),
+        4 => TileType::Baseline(Player::P1),
+        5 => TileType::Baseline(Player::P2),
+        _ => return Err(DecodeError::InvalidByte(byte)),
+    };
+    let piece_option = match byte & 0x1f {
+        0 => None,
+        piece @ 1..=16 => {
+            let piece = piece - 1;
+
+            Some(Piece {
+                owner: if piece & 8 == 0 {
+                    Player::P1
+                } else {
+                    Player::P2
+                },
+                exhausted: piece & 1 == 1,
+                piece_type: decode_piece_type((piece >> 1) & 3)?,
+            })
+        }
+        _ => return Err(DecodeError::InvalidByte(byte)),
+    };
+
+    Ok(Tile {
+        piece_option,
+        tile_type,
+    })
+}
+
+const fn piece_type_index(piece_type: PieceType) -> u8 {
+    match piece_type {
+        PieceType::Artillery => 0,
+        PieceType::Convoy => 1,
+        PieceType::Infantry => 2,
+        PieceType::Recon => 3,
+    }
+}
+
+fn decode_piece_type(byte: u8) -> Result<PieceType, DecodeError> {
+    PieceType::ALL
+        .get(usize::from(byte))
+        .copied()
+        .ok_or(DecodeError::InvalidByte(byte))
+}
@@ -1,3 +1,4 @@
+use crate::{coord::Coord, Phase};
 use thiserror::Error;
 
 #[cfg(feature = "serde")]
@@ -12,19 +13,237 @@ pub enum CommandError {
     Purchase(#[from]PurchaseError),
     #[error(transparent)]
     Battle(#[from]BattleError),
+    #[error("Commands cannot be given during the {0} phase")]
+    WrongPhase(Phase),
 }
 
 #[derive(Copy, Clone, Debug, Error, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[error("Move Error")]
-pub struct MoveError;
+pub enum MoveError {
+    #[error("There is no piece on {0}")]
+    NoPiece(Coord),
+    #[error("The piece on {0} belongs to the other player")]
+    NotYourPiece(Coord),
+    #[error("The piece on {0} is exhausted")]
+    PieceExhausted(Coord),
+    #[error("{0} is occupied")]
+    TileOccupied(Coord),
+    #[error("The piece can move {max} tiles but {actual} were needed")]
+    OutOfRange { max: u8, actual: u16 },
+    #[error("Every path to {0} is blocked")]
+    Blocked(Coord),
+    #[error("{0} is not on the board")]
+    OffBoard(Coord),
+}
+
+#[derive(Copy, Clone, Debug, Error, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PurchaseError {
+    #[error("The piece costs {cost} but only {available} is available")]
+    InsufficientFunds { cost: u8, available: u8 },
+    #[error("{0} does not produce your troops")]
+    NotAProductionTile(Coord),
+    #[error("{0} is occupied")]
+    TileOccupied(Coord),
+    #[error("{0} is not on the board")]
+    OffBoard(Coord),
+}
+
+#[derive(Copy, Clone, Debug, Error, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BattleError {
+    #[error("There is no piece to attack on {0}")]
+    NoTarget(Coord),
+    #[error("The piece on {0} is your own")]
+    TargetIsYours(Coord),
+    #[error("A battle needs at least one attacker")]
+    NoAttackers,
+    #[error("There is no piece on {0}")]
+    NoPiece(Coord),
+    #[error("The piece on {0} belongs to the other player")]
+    NotYourPiece(Coord),
+    #[error("The piece on {0} does not belong to the defender")]
+    NotDefendersPiece(Coord),
+    #[error("The piece on {0} is exhausted")]
+    PieceExhausted(Coord),
+    #[error("The piece on {0} is already taking part in the battle")]
+    AlreadyFighting(Coord),
+    #[error("The piece has a range of {min} to {max} but the target is {actual} away")]
+    OutOfRange { min: u8, max: u8, actual: u16 },
+    #[error("Only the target on {0} can retreat")]
+    NotTheTarget(Coord),
+    #[error("The target can only retreat once")]
+    MultipleRetreats,
+    #[error("{0} is not on the board")]
+    OffBoard(Coord),
+    #[error(transparent)]
+    Move(#[from] MoveError),
+}
+
+#[derive(Copy, Clone, Debug, Error, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UndoError {
+    #[error("There is nothing to undo")]
+    NothingToUndo,
+    #[error("There is nothing to redo")]
+    NothingToRedo,
+    #[error("Only actions from the current turn can be undone")]
+    TurnEnded,
+    #[error("Undoing is disabled")]
+    Disabled,
+}
+
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[error("Line {line}, column {column}: {kind}")]
+pub struct MapError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: MapErrorKind,
+}
+
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MapErrorKind {
+    #[error("Header lines must be written as `key: value`")]
+    MissingSeparator,
+    #[error("Only `\\\\`, `\\:`, `\\n` and `\\r` can be escaped")]
+    BadEscape,
+    #[error("`{0}` is not a tile")]
+    UnknownTile(String),
+    #[error("Every rank needs {expected} tiles but this one has {actual}")]
+    RaggedRank { expected: u8, actual: usize },
+    #[error("A map needs between 2 and 255 ranks, and between 1 and 255 files")]
+    BadSize,
+}
+
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GenerationError {
+    #[error("A board needs at least 2 ranks and 1 file")]
+    BadSize,
+    #[error("{requested} towns were requested but only {available} fit on the board")]
+    NotEnoughRoom { requested: u8, available: usize },
+    #[error("An odd number of towns needs a tile that is its own mirror image")]
+    NoCentre,
+}
+
+#[derive(Copy, Clone, Debug, Error, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ParseCoordError {
+    #[error("A coord starts with its rank letters")]
+    MissingRank,
+    #[error("A coord ends with its file number")]
+    MissingFile,
+    #[error("`{0}` cannot appear in a coord")]
+    UnexpectedCharacter(char),
+    #[error("The file number cannot start with a zero")]
+    LeadingZero,
+    #[error("The rank is larger than any board")]
+    RankTooLarge,
+    #[error("The file is larger than any board")]
+    FileTooLarge,
+    #[error("{0} is not on the board")]
+    OffBoard(Coord),
+}
 
 #[derive(Copy, Clone, Debug, Error, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[error("Purchase Error")]
-pub struct PurchaseError;
+pub enum ParseMoveError {
+    #[error("A move is written as two coords joined by `-`")]
+    MissingSeparator,
+    #[error(transparent)]
+    Coord(#[from] ParseCoordError),
+}
+
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ParseActionError {
+    #[error("`{0}` is not an action")]
+    Unrecognised(String),
+    #[error("`{0}` is not a piece letter")]
+    UnknownPiece(String),
+    #[error(transparent)]
+    Coord(#[from] ParseCoordError),
+    #[error(transparent)]
+    Move(#[from] ParseMoveError),
+}
+
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[error("Line {line}, column {column}: {kind}")]
+pub struct RecordError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: RecordErrorKind,
+}
+
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RecordErrorKind {
+    #[error("Tags must be written as `[Key \"value\"]`")]
+    BadTag,
+    #[error("Tags must come before the first action")]
+    LateTag,
+    #[error("Expected turn {expected} but found `{actual}.`")]
+    TurnNumber { expected: usize, actual: String },
+    #[error(transparent)]
+    Action(ParseActionError),
+}
+
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReplayError {
+    #[error("The starting position is invalid: {0}")]
+    Position(#[from] PositionError),
+    #[error("Action {index} was rejected: {error}")]
+    Rejected { index: usize, error: CommandError },
+    #[error("The game was over before action {index}")]
+    GameOver { index: usize },
+}
+
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PositionError {
+    #[error("A position needs {expected} fields but this one has {actual}")]
+    FieldCount { expected: usize, actual: usize },
+    #[error("`{0}` is not a tile")]
+    UnknownTile(char),
+    #[error("`{0}` is not a piece")]
+    UnknownPiece(char),
+    #[error("`{0}` ends with a count that repeats nothing")]
+    DanglingCount(String),
+    #[error("Rank {rank} needs {expected} tiles but has {actual}")]
+    RaggedRank {
+        rank: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Rank {rank} has more than {max} tiles")]
+    RankTooLong { rank: usize, max: usize },
+    #[error("A board needs between 2 and 255 ranks, and between 1 and 255 files")]
+    BadSize,
+    #[error("`{0}` is not a player")]
+    UnknownPlayer(String),
+    #[error("`{0}` is not a phase")]
+    UnknownPhase(String),
+    #[error("`{0}` is not a valid number")]
+    BadNumber(String),
+}
 
 #[derive(Copy, Clone, Debug, Error, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[error("Battle Error")]
-pub struct BattleError;
+pub enum DecodeError {
+    #[error("Version {0} of the encoding is not supported")]
+    UnsupportedVersion(u8),
+    #[error("The encoding ended unexpectedly")]
+    UnexpectedEnd,
+    #[error("The encoding has {0} bytes left over")]
+    TrailingBytes(usize),
+    #[error("{0:#04x} is not valid here")]
+    InvalidByte(u8),
+    #[error("{0} is too large")]
+    NumberTooLarge(u64),
+    #[error("A board needs at least 2 ranks and 1 file")]
+    BadSize,
+}
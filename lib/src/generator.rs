@@ -0,0 +1,222 @@
+//! Seeded generation of fair boards.
+
+use crate::{
+    board::Board,
+    coord::Coord,
+    errors::GenerationError,
+    rng::Rng,
+    tile::{Tile, TileType},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How the layout of [`Player::P2`](crate::Player::P2)'s half mirrors
+/// [`Player::P1`](crate::Player::P1)'s
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Symmetry {
+    /// Rotated half a turn around the centre of the board
+    #[default]
+    Point,
+    /// Reflected across the middle rank
+    Mirror,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GeneratorSettings {
+    pub ranks: u8,
+    pub files: u8,
+    pub town_count: u8,
+    /// The percentage chance of placing each town near the towns already placed
+    pub clustering: u8,
+    /// The fewest ranks between any town and either baseline
+    pub min_baseline_distance: u8,
+    pub symmetry: Symmetry,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        let board = Board::default();
+
+        Self {
+            ranks: board.ranks(),
+            files: board.files(),
+            town_count: 8,
+            clustering: 30,
+            min_baseline_distance: 3,
+            symmetry: Symmetry::default(),
+        }
+    }
+}
+
+impl GeneratorSettings {
+    /// The distance within which a town counts as near another
+    const CLUSTER_DISTANCE: u8 = 2;
+
+    /// Generates a board with towns placed symmetrically between both players. The same settings
+    /// and seed always generate the same board.
+    ///
+    /// # Errors
+    ///
+    /// Will return Err if the board size is invalid, or the towns do not fit on the board given the
+    /// other settings
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use convoy::{
+    ///     coord::Coord,
+    ///     generator::{GeneratorSettings, Symmetry},
+    ///     tile::TileType,
+    ///     GenerationError,
+    /// };
+    ///
+    /// for symmetry in [Symmetry::Point, Symmetry::Mirror] {
+    ///     let settings = GeneratorSettings { symmetry, ..GeneratorSettings::default() };
+    ///     let (ranks, files) = (settings.ranks, settings.files);
+    ///
+    ///     for seed in 0..20 {
+    ///         let board = settings.generate(seed).unwrap();
+    ///         assert_eq!(settings.generate(seed).as_ref(), Ok(&board));
+    ///
+    ///         let towns: Vec<Coord> = board
+    ///             .coords()
+    ///             .filter(|&coord| board[coord].tile_type == TileType::Town(None))
+    ///             .collect();
+    ///         assert_eq!(towns.len(), usize::from(settings.town_count));
+    ///
+    ///         for Coord { rank, file } in towns {
+    ///             assert!(rank >= settings.min_baseline_distance);
+    ///             assert!(ranks - 1 - rank >= settings.min_baseline_distance);
+    ///
+    ///             let file = match symmetry {
+    ///                 Symmetry::Point => files - 1 - file,
+    ///                 Symmetry::Mirror => file,
+    ///             };
+    ///             let mirror = Coord { rank: ranks - 1 - rank, file };
+    ///             assert_eq!(board[mirror].tile_type, TileType::Town(None));
+    ///         }
+    ///     }
+    ///
+    ///     assert_ne!(settings.generate(0), settings.generate(1));
+    /// }
+    ///
+    /// // An odd number of towns needs the centre tile, which only odd sizes have
+    /// let settings = GeneratorSettings {
+    ///     ranks: 10,
+    ///     files: 9,
+    ///     town_count: 3,
+    ///     ..GeneratorSettings::default()
+    /// };
+    /// assert_eq!(settings.generate(0), Err(GenerationError::NoCentre));
+    ///
+    /// let settings = GeneratorSettings { ranks: 11, ..settings };
+    /// let board = settings.generate(0).unwrap();
+    /// assert_eq!(board[Coord { rank: 5, file: 4 }].tile_type, TileType::Town(None));
+    ///
+    /// let settings = GeneratorSettings { ranks: 7, files: 1, ..settings };
+    /// assert_eq!(
+    ///     settings.generate(0),
+    ///     Err(GenerationError::NotEnoughRoom { requested: 3, available: 1 })
+    /// );
+    /// ```
+    pub fn generate(&self, seed: u64) -> Result<Board, GenerationError> {
+        let mut board = Board::with_size(self.ranks, self.files).ok_or(GenerationError::BadSize)?;
+        let mut rng = Rng::new(seed);
+
+        // Each candidate stands for itself and its mirror image, which may be the same tile
+        let mut candidates: Vec<Coord> = board
+            .coords()
+            .filter(|&coord| {
+                let mirror = self.mirror(coord);
+
+                coord.rank >= self.min_baseline_distance.max(1)
+                    && mirror.rank >= self.min_baseline_distance.max(1)
+                    && (coord.rank, coord.file) <= (mirror.rank, mirror.file)
+            })
+            .collect();
+
+        let available = candidates
+            .iter()
+            .map(|&coord| if self.mirror(coord) == coord { 1 } else { 2 })
+            .sum::<usize>();
+
+        let not_enough_room = GenerationError::NotEnoughRoom {
+            requested: self.town_count,
+            available,
+        };
+
+        if usize::from(self.town_count) > available {
+            return Err(not_enough_room);
+        }
+
+        if self.town_count % 2 == 1 && !candidates.iter().any(|&coord| self.mirror(coord) == coord)
+        {
+            return Err(GenerationError::NoCentre);
+        }
+
+        let mut towns = Vec::new();
+
+        while towns.len() < usize::from(self.town_count) {
+            // An odd number of towns left needs a tile that is its own mirror image
+            let odd = (usize::from(self.town_count) - towns.len()) % 2 == 1;
+            let mut fitting: Vec<Coord> = candidates
+                .iter()
+                .filter(|&&coord| (self.mirror(coord) == coord) == odd)
+                .copied()
+                .collect();
+
+            if fitting.is_empty() {
+                fitting.clone_from(&candidates);
+            }
+
+            let clustered: Vec<Coord> = fitting
+                .iter()
+                .filter(|&&coord| {
+                    towns
+                        .iter()
+                        .any(|&town| coord.distance(town) <= u16::from(Self::CLUSTER_DISTANCE))
+                })
+                .copied()
+                .collect();
+
+            let pool = if !clustered.is_empty() && rng.percent(self.clustering) {
+                clustered
+            } else {
+                fitting
+            };
+            let &town = rng.choose(&pool).ok_or_else(|| not_enough_room.clone())?;
+
+            candidates.retain(|&coord| coord != town);
+            towns.push(town);
+
+            if self.mirror(town) != town {
+                towns.push(self.mirror(town));
+            }
+        }
+
+        for town in towns {
+            board[town] = Tile {
+                piece_option: None,
+                tile_type: TileType::Town(None),
+            };
+        }
+
+        Ok(board)
+    }
+
+    /// The coord mirroring `coord` in the other player's half
+    const fn mirror(&self, Coord { rank, file }: Coord) -> Coord {
+        let rank = self.ranks - 1 - rank;
+
+        match self.symmetry {
+            Symmetry::Point => Coord {
+                rank,
+                file: self.files - 1 - file,
+            },
+            Symmetry::Mirror => Coord { rank, file },
+        }
+    }
+}
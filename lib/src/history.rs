@@ -0,0 +1,262 @@
+use crate::{coord::Coord, rules::UndoPolicy, tile::Tile, Action, Game, Phase, Player, UndoError};
+use std::mem;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The actions taken so far, each paired with the changes that take the game back across it.
+///
+/// No changes are kept when undoing is disabled.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct History {
+    /// The position the game started from, as a position string
+    start: String,
+    /// Taken actions with the changes undoing them
+    past: Vec<(Action, Vec<Change>)>,
+    /// Undone actions with the changes redoing them
+    future: Vec<(Action, Vec<Change>)>,
+    /// The changes made so far by the action being taken
+    pending: Vec<Change>,
+}
+
+/// A single change to the game, holding the value it replaced so that applying the change again
+/// reverts it
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Change {
+    Tile(Coord, Tile),
+    Money(Player, u8),
+    Player(Player),
+    Phase(Phase),
+    Counters {
+        turn: u16,
+        turns_without_progress: u16,
+        progressed: bool,
+    },
+    PushPosition(u64),
+    PopPosition,
+}
+
+impl Game {
+    /// The position the game started from before any of its actions were taken, see
+    /// [`Game::to_position_string`]
+    #[must_use]
+    pub fn start_position(&self) -> &str {
+        &self.history.start
+    }
+
+    /// Every action taken so far, in order
+    pub fn history(&self) -> impl Iterator<Item = &Action> {
+        self.history.past.iter().map(|(action, _)| action)
+    }
+
+    #[must_use]
+    pub fn can_undo(&self) -> bool {
+        self.check_undo().is_ok()
+    }
+
+    #[must_use]
+    pub const fn can_redo(&self) -> bool {
+        !self.history.future.is_empty()
+    }
+
+    /// Restores the game to how it was before the last action
+    ///
+    /// # Errors
+    ///
+    /// Will return Err if nothing has happened yet or the undo policy forbids undoing the last
+    /// action
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use convoy::{rules::RuleSet, Action, ActionOutcome, Game};
+    ///
+    /// let start = "3B/3./3./3b I1I/3/3/1i1 1 C 3,3 0 0";
+    /// let play = |actions: [&str; 2]| {
+    ///     let mut game = Game::from_position_string(start, RuleSet::default()).unwrap();
+    ///
+    ///     for action in actions {
+    ///         let action: Action = action.parse().unwrap();
+    ///         let Ok(ActionOutcome::Ongoing(next)) = game.take_action(action) else {
+    ///             panic!("The moves are legal");
+    ///         };
+    ///         game = next;
+    ///     }
+    ///
+    ///     game
+    /// };
+    ///
+    /// // The same position reached in a different order is the same game
+    /// let mut game = play(["a0-b0", "a2-b2"]);
+    /// assert_eq!(game, play(["a2-b2", "a0-b0"]));
+    ///
+    /// game.undo().unwrap();
+    /// game.undo().unwrap();
+    /// assert_eq!(game.to_position_string(), start);
+    /// assert!(!game.can_undo());
+    ///
+    /// game.redo().unwrap();
+    /// assert_eq!(game.position_hash(), game.compute_position_hash());
+    /// ```
+    pub fn undo(&mut self) -> Result<(), UndoError> {
+        self.check_undo()?;
+
+        let Some((action, changes)) = self.history.past.pop() else {
+            unreachable!("Undoable actions have been taken");
+        };
+        let redo = self.apply(changes);
+        self.history.future.push((action, redo));
+
+        Ok(())
+    }
+
+    /// Takes the last undone action again
+    ///
+    /// # Errors
+    ///
+    /// Will return Err if there is no undone action left
+    pub fn redo(&mut self) -> Result<(), UndoError> {
+        let (action, changes) = self.history.future.pop().ok_or(UndoError::NothingToRedo)?;
+        let undo = self.apply(changes);
+        self.history.past.push((action, undo));
+
+        Ok(())
+    }
+
+    /// A copy of the game with an empty history, starting from the current position but still
+    /// remembering the positions seen so far and the turns without progress so that draws can be
+    /// foreseen. Unlike a clone, its cost does not
+    /// grow with the length of the game, which suits searching ahead.
+    ///
+    /// ```
+    /// use convoy::{ActionOutcome, Game};
+    ///
+    /// let ActionOutcome::Ongoing(game) = Game::new().end_turn() else {
+    ///     panic!("The game goes on");
+    /// };
+    /// let copy = game.without_history();
+    ///
+    /// assert_eq!(*copy, *game);
+    /// assert_eq!(copy.to_position_string(), game.to_position_string());
+    /// assert_eq!(copy.history().count(), 0);
+    /// assert_eq!(copy.start_position(), game.to_position_string());
+    /// ```
+    #[must_use]
+    pub fn without_history(&self) -> Box<Self> {
+        Box::new(Self {
+            board: self.board.clone(),
+            player_money: self.player_money,
+            cur_player: self.cur_player,
+            phase: self.phase,
+            rules: self.rules.clone(),
+            turn: self.turn,
+            turns_without_progress: self.turns_without_progress,
+            progressed: self.progressed,
+            positions: self.positions.clone(),
+            hash: self.hash,
+            history: History {
+                start: self.to_position_string(),
+                ..History::default()
+            },
+        })
+    }
+
+    /// Makes the current position the game's start, once it has been set up
+    pub(crate) fn start(&mut self) {
+        self.hash = self.compute_position_hash();
+        self.positions = vec![self.hash];
+        self.history = History {
+            start: self.to_position_string(),
+            ..History::default()
+        };
+    }
+
+    fn check_undo(&self) -> Result<(), UndoError> {
+        let (action, _) = self.history.past.last().ok_or(UndoError::NothingToUndo)?;
+
+        match (self.rules.undo_policy, action) {
+            (UndoPolicy::Disabled, _) => Err(UndoError::Disabled),
+            (UndoPolicy::CurrentTurn, Action::EndPhase | Action::EndTurn) => {
+                Err(UndoError::TurnEnded)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Starts recording the changes made by the action about to be taken
+    pub(crate) fn begin(&mut self) {
+        self.history.pending.clear();
+        self.log(Change::Counters {
+            turn: self.turn,
+            turns_without_progress: self.turns_without_progress,
+            progressed: self.progressed,
+        });
+    }
+
+    /// Notes `change` as made by the action being taken, unless undoing is disabled
+    pub(crate) fn log(&mut self, change: Change) {
+        if self.rules.undo_policy != UndoPolicy::Disabled {
+            self.history.pending.push(change);
+        }
+    }
+
+    /// Records `action` as just taken, forgetting any undone actions
+    pub(crate) fn record(&mut self, action: Action) {
+        debug_assert_eq!(
+            self.position_hash(),
+            self.compute_position_hash(),
+            "The position hash was not kept up to date"
+        );
+
+        let changes = mem::take(&mut self.history.pending);
+        self.history.past.push((action, changes));
+        self.history.future.clear();
+    }
+
+    /// Reverts `changes`, returning the changes which revert them in turn
+    fn apply(&mut self, changes: Vec<Change>) -> Vec<Change> {
+        self.history.pending.clear();
+
+        for change in changes.into_iter().rev() {
+            match change {
+                Change::Tile(coord, tile) => self.set_tile(coord, tile),
+                Change::Money(player, money) => self.set_money(player, money),
+                Change::Player(player) => self.set_cur_player(player),
+                Change::Phase(phase) => self.set_phase(phase),
+                Change::Counters {
+                    turn,
+                    turns_without_progress,
+                    progressed,
+                } => {
+                    self.log(Change::Counters {
+                        turn: self.turn,
+                        turns_without_progress: self.turns_without_progress,
+                        progressed: self.progressed,
+                    });
+                    self.turn = turn;
+                    self.turns_without_progress = turns_without_progress;
+                    self.progressed = progressed;
+                }
+                Change::PushPosition(hash) => {
+                    self.positions.push(hash);
+                    self.log(Change::PopPosition);
+                }
+                Change::PopPosition => {
+                    if let Some(hash) = self.positions.pop() {
+                        self.log(Change::PushPosition(hash));
+                    }
+                }
+            }
+        }
+
+        debug_assert_eq!(
+            self.position_hash(),
+            self.compute_position_hash(),
+            "The position hash was not kept up to date"
+        );
+
+        mem::take(&mut self.history.pending)
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{
+    board::Board,
+    coord::{Coord, Move},
+    pieces::PieceType,
+    Action, AttackCommand, Command, DefenseCommand, Game, Phase,
+};
+use std::{cmp::Reverse, iter};
+
+impl Game {
+    /// The actions worth considering for the current player, every one of them legal.
+    ///
+    /// This is every move and purchase and [`Action::EndTurn`], but not every legal action:
+    ///
+    /// - battles are limited to a bounded set of attacker groups, see [`Game::candidate_battles`]
+    /// - no battle has [`DefenseCommand::Defend`] commands, which only weaken the attack
+    /// - [`Action::EndPhase`] is left out, as it only steps through what [`Action::EndTurn`] does
+    ///   at once
+    #[must_use]
+    pub fn candidate_actions(&self) -> Vec<Action> {
+        self.legal_moves()
+            .map(Command::Move)
+            .chain(
+                self.legal_purchases()
+                    .map(|(piece_type, coord)| Command::Purchase(piece_type, coord)),
+            )
+            .chain(self.candidate_battles())
+            .map(Action::Command)
+            .chain([Action::EndTurn])
+            .collect()
+    }
+
+    /// Every move of one of the current player's unexhausted pieces
+    pub fn legal_moves(&self) -> impl Iterator<Item = Move> + '_ {
+        self.ready_pieces().flat_map(move |from| {
+            self.board
+                .get_moves(from, &self.rules)
+                .unwrap_or_default()
+                .into_iter()
+                .map(move |to| Move { from, to })
+        })
+    }
+
+    /// Every piece the current player can afford, paired with each free tile producing it
+    pub fn legal_purchases(&self) -> impl Iterator<Item = (PieceType, Coord)> + '_ {
+        PieceType::ALL
+            .into_iter()
+            .filter(|piece_type| {
+                self.phase == Phase::Command
+                    && self.rules[*piece_type].cost <= self[self.cur_player]
+            })
+            .flat_map(move |piece_type| {
+                self.board
+                    .coords()
+                    .filter(|&coord| {
+                        self.board[coord].produces_troops(self.cur_player)
+                            && self.board[coord].piece_option.is_none()
+                    })
+                    .map(move |coord| (piece_type, coord))
+            })
+    }
+
+    /// Some of the legal battles against each enemy piece, fought by canonical groups of the
+    /// current player's unexhausted pieces, with and without the target retreating.
+    ///
+    /// Trying every group of attackers would take exponential time, so for each target only these
+    /// groups are generated, with `n` pieces already in range and `m` moves bringing another piece
+    /// into range:
+    ///
+    /// - the strongest `k` pieces in range for each `k`, the smallest winning group among them
+    ///   being as small as any winning group can be
+    /// - each piece in range on its own
+    /// - each [`AttackCommand::MoveAttack`] on its own, and joined by every piece in range
+    ///
+    /// That is at most `2n + 2m` groups, each generated once without defense commands and once
+    /// with each [`DefenseCommand::Retreat`] open to the target. Supporting defenders are left for
+    /// the defender to add.
+    ///
+    /// ```
+    /// use convoy::{rules::RuleSet, Action, Game};
+    ///
+    /// let position = "3B/3./3./3b 1I1/3/1i1/3 1 C 3,3 0 0";
+    /// let game = Game::from_position_string(position, RuleSet::default()).unwrap();
+    /// let battles: Vec<Action> = game.candidate_battles().map(Action::Command).collect();
+    ///
+    /// // The infantry on a1 moves next to the target, which may retreat
+    /// for battle in ["a1-b1>c1", "a1-b1>c1/c1-c0"] {
+    ///     assert!(battles.contains(&battle.parse().unwrap()));
+    /// }
+    ///
+    /// for battle in battles {
+    ///     assert!(game.clone().take_action(battle).is_ok());
+    /// }
+    /// ```
+    pub fn candidate_battles(&self) -> impl Iterator<Item = Command> + '_ {
+        self.board
+            .coords()
+            .filter(|&coord| {
+                self.board[coord]
+                    .piece_option
+                    .is_some_and(|piece| piece.owner != self.cur_player)
+            })
+            .flat_map(move |target| self.battles_against(target))
+    }
+
+    /// The battles against `target` described by [`Game::candidate_battles`]
+    fn battles_against(&self, target: Coord) -> Vec<Command> {
+        let in_range = |piece_type: PieceType, coord: Coord| {
+            coord.in_range(target, &self.rules[piece_type].range)
+        };
+        let power = |coord: Coord| {
+            self.board[coord]
+                .piece_option
+                .map_or(0, |piece| self.rules[piece.piece_type].power)
+        };
+
+        let mut attackers = Vec::new();
+        let mut move_attacks = Vec::new();
+
+        for from in self.ready_pieces() {
+            let Some(piece) = self.board[from].piece_option else {
+                continue;
+            };
+
+            if in_range(piece.piece_type, from) {
+                attackers.push(from);
+            } else {
+                move_attacks.extend(
+                    self.board
+                        .get_moves(from, &self.rules)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|&to| in_range(piece.piece_type, to))
+                        .map(|to| Move { from, to }),
+                );
+            }
+        }
+
+        // Strongest first, so that each prefix is the strongest group of its size
+        attackers.sort_by_key(|&coord| Reverse(power(coord)));
+
+        let groups = (1..=attackers.len())
+            .map(|count| attack_all(&attackers[..count]))
+            .chain(attackers.iter().skip(1).map(|&coord| attack_all(&[coord])));
+        let retreats = self.retreats(&self.board, target);
+        let mut battles: Vec<Command> = groups
+            .flat_map(|group| with_retreats(&group, target, &retreats))
+            .collect();
+
+        for r#move in move_attacks {
+            let mut board = self.board.clone();
+            board[r#move.to].piece_option = board[r#move.from].piece_option.take();
+            let retreats = self.retreats(&board, target);
+            let alone = [AttackCommand::MoveAttack(r#move)];
+
+            battles.extend(with_retreats(&alone, target, &retreats));
+
+            if !attackers.is_empty() {
+                let mut joined = attack_all(&attackers);
+                joined.extend(alone);
+                battles.extend(with_retreats(&joined, target, &retreats));
+            }
+        }
+
+        battles
+    }
+
+    /// The moves open to the piece on `target` once the attackers have moved on `board`
+    fn retreats(&self, board: &Board, target: Coord) -> Vec<Move> {
+        board
+            .get_moves(target, &self.rules)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|to| Move { from: target, to })
+            .collect()
+    }
+
+    /// The coords of the current player's unexhausted pieces, if they can be commanded
+    fn ready_pieces(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.board.coords().filter(|&coord| {
+            self.phase == Phase::Command
+                && self.board[coord]
+                    .piece_option
+                    .is_some_and(|piece| piece.owner == self.cur_player && !piece.exhausted)
+        })
+    }
+}
+
+fn attack_all(coords: &[Coord]) -> Vec<AttackCommand> {
+    coords.iter().copied().map(AttackCommand::Attack).collect()
+}
+
+/// A battle fought by `attack_commands` without defense commands, then one for each retreat
+fn with_retreats(
+    attack_commands: &[AttackCommand],
+    target: Coord,
+    retreats: &[Move],
+) -> Vec<Command> {
+    iter::once(None)
+        .chain(retreats.iter().copied().map(Some))
+        .map(|retreat| Command::Battle {
+            attack_commands: attack_commands.to_vec(),
+            defense_commands: retreat.map(DefenseCommand::Retreat).into_iter().collect(),
+            target,
+        })
+        .collect()
+}
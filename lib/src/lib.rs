@@ -1,20 +1,44 @@
+pub mod agent;
+pub mod analysis;
 pub mod board;
 pub mod constants;
 pub mod coord;
+pub mod encoding;
 mod errors;
+pub mod generator;
+mod history;
+mod legal;
+pub mod map;
+mod notation;
 pub mod pieces;
+mod position;
+pub mod record;
+pub mod rng;
+pub mod rules;
 pub mod tile;
+mod zobrist;
 
 use board::Board;
 use coord::{Coord, Move};
-pub use errors::{BattleError, CommandError, MoveError, PurchaseError};
+pub use errors::{
+    BattleError, CommandError, DecodeError, GenerationError, MapError, MapErrorKind, MoveError,
+    ParseActionError, ParseCoordError, ParseMoveError, PositionError, PurchaseError, RecordError,
+    RecordErrorKind, ReplayError, UndoError,
+};
+use history::{Change, History};
 use pieces::{Piece, PieceType};
-use std::ops::Index;
+use rules::{RuleSet, VictoryConditions};
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+    mem,
+    ops::Index,
+};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Player {
     #[default]
@@ -22,10 +46,50 @@ pub enum Player {
     P2,
 }
 
+impl Player {
+    #[must_use]
+    pub const fn opponent(self) -> Self {
+        match self {
+            Self::P1 => Self::P2,
+            Self::P2 => Self::P1,
+        }
+    }
+}
+
+/// The phases of a turn, in the order they are played
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Phase {
+    /// The player is paid and their pieces are unexhausted
+    Upkeep,
+    /// The player gives commands
+    #[default]
+    Command,
+    /// The player's unsupplied pieces are destroyed
+    Resupply,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Upkeep => "Upkeep",
+                Self::Command => "Command",
+                Self::Resupply => "Resupply",
+            }
+        )
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Action {
     Command(Command),
+    /// Moves on to the next phase, resolving its effects
+    EndPhase,
+    /// Moves on through the phases until the next command phase
     EndTurn,
 }
 
@@ -64,12 +128,21 @@ pub enum DefenseCommand {
     Retreat(Move),
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Game {
     board: Board,
     player_money: [u8; 2],
     cur_player: Player,
+    phase: Phase,
+    rules: RuleSet,
+    turn: u16,
+    turns_without_progress: u16,
+    progressed: bool,
+    positions: Vec<u64>,
+    /// Kept equal to [`Game::compute_position_hash`] by every change to the game
+    hash: u64,
+    history: History,
 }
 
 impl Game {
@@ -78,6 +151,31 @@ impl Game {
         Box::new(Self::default())
     }
 
+    #[must_use]
+    pub fn with_rules(rules: RuleSet) -> Box<Self> {
+        Self::with_board(Board::new(), rules)
+    }
+
+    #[must_use]
+    pub fn with_board(board: Board, rules: RuleSet) -> Box<Self> {
+        let mut game = Box::new(Self {
+            board,
+            player_money: [rules.starting_money; 2],
+            cur_player: Player::P1,
+            phase: Phase::Command,
+            rules,
+            turn: 0,
+            turns_without_progress: 0,
+            progressed: false,
+            positions: Vec::new(),
+            hash: 0,
+            history: History::default(),
+        });
+        game.start();
+
+        game
+    }
+
     #[must_use]
     pub const fn board(&self) -> &Board {
         &self.board
@@ -87,25 +185,75 @@ impl Game {
     pub const fn cur_player(&self) -> Player {
         self.cur_player
     }
+
+    #[must_use]
+    pub const fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    #[must_use]
+    pub const fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// The number of turns that have been ended so far
+    #[must_use]
+    pub const fn turn(&self) -> u16 {
+        self.turn
+    }
+
+    /// See [`Board::logistics_network`]
+    #[must_use]
+    pub fn logistics_network(&self, player: Player) -> HashSet<Coord> {
+        self.board.logistics_network(player)
+    }
+
+    /// The money `player` gains during their upkeep, for each of their towns connected to their
+    /// logistics network on top of the base income
+    #[must_use]
+    pub fn income(&self, player: Player) -> u8 {
+        let network = self.logistics_network(player);
+        let towns = self
+            .board
+            .coords()
+            .filter(|coord| self.board[*coord].gives_income(player) && network.contains(coord))
+            .count();
+
+        u8::try_from(towns)
+            .unwrap_or(u8::MAX)
+            .saturating_mul(self.rules.town_income)
+            .saturating_add(self.rules.base_income)
+    }
 }
 
 impl Game {
-    ///
-    ///
-    /// # Arguments
-    ///
-    /// *
-    ///
-    /// returns:
+    /// Takes `action` for the current player, handing the game back along with the error if it
+    /// is rejected
     ///
     /// # Errors
     ///
-    ///
+    /// Will return Err if `action` is a command that is not possible, including one naming coords
+    /// off the board
     ///
     /// # Examples
     ///
     /// ```
-    ///
+    /// use convoy::{Action, BattleError, CommandError, Game, MoveError, PurchaseError};
+    ///
+    /// let mut game = Game::new();
+    ///
+    /// for (action, expected) in [
+    ///     ("z1-a1", CommandError::Move(MoveError::OffBoard("z1".parse().unwrap()))),
+    ///     ("a1-a99", CommandError::Move(MoveError::OffBoard("a99".parse().unwrap()))),
+    ///     ("I@z1", CommandError::Purchase(PurchaseError::OffBoard("z1".parse().unwrap()))),
+    ///     ("a1>z1", CommandError::Battle(BattleError::OffBoard("z1".parse().unwrap()))),
+    /// ] {
+    ///     let action: Action = action.parse().unwrap();
+    ///     let (returned, error) = game.take_action(action).unwrap_err();
+    ///
+    ///     assert_eq!(error, expected);
+    ///     game = returned;
+    /// }
     /// ```
     pub fn take_action(
         mut self: Box<Self>,
@@ -113,218 +261,618 @@ impl Game {
     ) -> Result<ActionOutcome, (Box<Self>, CommandError)> {
         match action {
             Action::Command(command) => match self.do_command(command) {
-                Ok(()) => Ok(ActionOutcome::Ongoing(self)),
+                Ok(()) => Ok(self.outcome()),
                 Err(err) => Err((self, err)),
             },
+            Action::EndPhase => Ok(self.end_phase()),
             Action::EndTurn => Ok(self.end_turn()),
         }
     }
 
-    ///
-    ///
-    /// # Arguments
-    ///
-    /// *
-    ///
-    /// returns:
+    /// Gives `command` for the current player
     ///
     /// # Errors
     ///
-    ///
-    ///
-    /// # Examples
-    ///
-    /// ```
-    ///
-    /// ```
+    /// Will return Err if it is not the command phase or `command` is not possible, in which case
+    /// the game is left untouched
     pub fn do_command(&mut self, command: Command) -> Result<(), CommandError> {
-        match command {
-            Command::Move(r#move) => self.do_move(r#move).map_err(CommandError::Move),
-            Command::Purchase(piece_type, coord) => self
-                .do_purchase(piece_type, coord)
-                .map_err(CommandError::Purchase),
+        if self.phase != Phase::Command {
+            return Err(CommandError::WrongPhase(self.phase));
+        }
+
+        self.begin();
+
+        match command.clone() {
+            Command::Move(r#move) => self.resolve_move(r#move)?,
+            Command::Purchase(piece_type, coord) => self.resolve_purchase(piece_type, coord)?,
             Command::Battle {
                 attack_commands,
                 defense_commands,
                 target,
-            } => self
-                .do_battle(attack_commands, defense_commands, target)
-                .map_err(CommandError::Battle),
+            } => self.resolve_battle(attack_commands, defense_commands, target)?,
         }
+
+        self.record(Action::Command(command));
+
+        Ok(())
     }
 
-    ///
-    ///
-    /// # Arguments
-    ///
-    /// * `command`:
-    ///
-    /// returns: Result<(), `CommandError`>
+    /// Moves the current player's piece along `move`, exhausting it and capturing any town it
+    /// stops on
     ///
     /// # Errors
     ///
-    /// Will return Err if `command` is not possible
+    /// Will return Err if it is not the command phase, there is no piece on `from`, it belongs to
+    /// the other player, it is exhausted, or it cannot reach `to`
     ///
     /// # Examples
     ///
     /// ```
+    /// use convoy::{rules::RuleSet, ActionOutcome, CommandError, Game, Phase, Player};
+    ///
+    /// let upkeep = "3B/3./3./3b I2/3/3/1i1 1 U 3,3 0 0";
+    /// let mut game = Game::from_position_string(upkeep, RuleSet::default()).unwrap();
+    ///
+    /// assert_eq!(
+    ///     game.do_move("a0-b0".parse().unwrap()),
+    ///     Err(CommandError::WrongPhase(Phase::Upkeep))
+    /// );
+    ///
+    /// // Ending the turn from the upkeep only finishes the upkeep
+    /// let ActionOutcome::Ongoing(mut game) = game.end_turn() else {
+    ///     panic!("The game goes on");
+    /// };
+    /// assert_eq!((game.cur_player(), game.phase()), (Player::P1, Phase::Command));
+    /// assert_eq!(game.do_move("a0-b0".parse().unwrap()), Ok(()));
+    /// ```
+    ///
+    /// Moves which break the rules leave the game untouched:
     ///
     /// ```
-    pub fn do_move(&mut self, Move { from, to }: Move) -> Result<(), MoveError> {
-        if !self
-            .board
-            .get_moves(from)
-            .is_some_and(|moves| moves.contains(&to))
-        {
-            return Err(MoveError);
-        }
+    /// use convoy::{rules::RuleSet, CommandError, Game, MoveError};
+    ///
+    /// // b0 is walled in by the pieces on a0, b1 and c0
+    /// let position = "3B/3./3./3b I'I1/1I1/I1I'/1ii 1 C 3,3 0 0";
+    /// let mut game = Game::from_position_string(position, RuleSet::default()).unwrap();
+    /// let mut do_move = |r#move: &str| match game.do_move(r#move.parse().unwrap()) {
+    ///     Err(CommandError::Move(error)) => error,
+    ///     result => panic!("{move} should not be allowed, got {result:?}"),
+    /// };
+    ///
+    /// assert_eq!(do_move("d1-c1"), MoveError::NotYourPiece("d1".parse().unwrap()));
+    /// assert_eq!(do_move("a0-b0"), MoveError::PieceExhausted("a0".parse().unwrap()));
+    /// assert_eq!(do_move("b1-d0"), MoveError::OutOfRange { max: 2, actual: 3 });
+    /// assert_eq!(do_move("a1-b0"), MoveError::Blocked("b0".parse().unwrap()));
+    /// assert_eq!(game.to_position_string(), position);
+    /// ```
+    pub fn do_move(&mut self, r#move: Move) -> Result<(), CommandError> {
+        self.do_command(Command::Move(r#move))
+    }
+
+    fn resolve_move(&mut self, r#move: Move) -> Result<(), MoveError> {
+        let piece = self.check_move(&self.board, r#move)?;
+        let Move { from, to } = r#move;
 
-        self.board[to].piece_option = self.board[from].piece_option.map(|piece| Piece {
+        let mut tile = self.board[to];
+        tile.piece_option = Some(Piece {
             exhausted: true,
             ..piece
         });
-        self.board[from].piece_option = None;
+        self.progressed |= tile.capture(piece.owner);
+
+        self.set_tile(to, tile);
+        self.set_piece(from, None);
 
         Ok(())
     }
 
-    ///
-    ///
-    /// # Arguments
-    ///
-    /// *
-    ///
-    /// returns:
+    /// Buys a `piece_type` for the current player, placing it on `coord`
     ///
     /// # Errors
     ///
-    ///
-    ///
-    /// # Examples
-    ///
-    /// ```
-    ///
-    /// ```
+    /// Will return Err if it is not the command phase, the player cannot afford the piece, or
+    /// `coord` does not produce their troops or is occupied
     pub fn do_purchase(
         &mut self,
         piece_type: PieceType,
         coord: Coord,
+    ) -> Result<(), CommandError> {
+        self.do_command(Command::Purchase(piece_type, coord))
+    }
+
+    fn resolve_purchase(
+        &mut self,
+        piece_type: PieceType,
+        coord: Coord,
     ) -> Result<(), PurchaseError> {
-        if piece_type.cost() > self[self.cur_player] {
-            return Err(PurchaseError);
+        if !self.board.contains(coord) {
+            return Err(PurchaseError::OffBoard(coord));
+        }
+
+        let cost = self.rules[piece_type].cost;
+
+        if cost > self[self.cur_player] {
+            return Err(PurchaseError::InsufficientFunds {
+                cost,
+                available: self[self.cur_player],
+            });
         }
 
         if !self.board[coord].produces_troops(self.cur_player) {
-            return Err(PurchaseError);
+            return Err(PurchaseError::NotAProductionTile(coord));
         }
 
         if self.board[coord].piece_option.is_some() {
-            return Err(PurchaseError);
+            return Err(PurchaseError::TileOccupied(coord));
         }
 
-        *self.index_mut(self.cur_player) -= piece_type.cost();
-        self.board[coord].piece_option = Some(Piece::new(self.cur_player, piece_type));
+        self.set_money(self.cur_player, self[self.cur_player] - cost);
+        self.set_piece(coord, Some(Piece::new(self.cur_player, piece_type)));
+        self.progressed = true;
 
         Ok(())
     }
 
-    ///
+    /// Resolves a battle against the enemy piece on `target`.
     ///
     /// # Arguments
     ///
-    /// * `command`:
+    /// * `attack_commands`: the current player's pieces joining the attack, optionally moving first
+    /// * `defense_commands`: the enemy pieces supporting the target, and an optional retreat for it
+    /// * `target`: the coord of the enemy piece being attacked
     ///
     /// returns: Result<(), `CommandError`>
     ///
+    /// The attack succeeds when the summed power of the attackers exceeds the power of the target
+    /// plus that of its supporters. A beaten target retreats along its retreat move if it was
+    /// given one and is destroyed otherwise. Every attacker is exhausted whatever the result.
+    ///
     /// # Errors
     ///
-    /// Will return Err if `command` is not possible
+    /// Will return Err if it is not the command phase or any of the commands is not possible, in
+    /// which case the game is left untouched
     ///
     /// # Examples
     ///
     /// ```
-    ///
+    /// use convoy::{rules::RuleSet, AttackCommand, BattleError, CommandError, Game};
+    ///
+    /// let position = "3B/3./3./3b I'I1/1I1/I1I'/1ii 1 C 3,3 0 0";
+    /// let mut game = Game::from_position_string(position, RuleSet::default()).unwrap();
+    /// let target = "d2".parse().unwrap();
+    /// let mut attack = |attacker: &str| {
+    ///     let attack_commands = vec![AttackCommand::Attack(attacker.parse().unwrap())];
+    ///
+    ///     match game.do_battle(attack_commands, Vec::new(), target) {
+    ///         Err(CommandError::Battle(error)) => error,
+    ///         result => panic!("{attacker} should not attack, got {result:?}"),
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(attack("d1"), BattleError::NotYourPiece("d1".parse().unwrap()));
+    /// assert_eq!(attack("c2"), BattleError::PieceExhausted("c2".parse().unwrap()));
+    /// assert_eq!(attack("b1"), BattleError::OutOfRange { min: 1, max: 1, actual: 3 });
+    /// assert_eq!(game.to_position_string(), position);
     /// ```
-    #[allow(clippy::unnecessary_wraps)]
-    #[allow(clippy::unused_self)]
-    #[allow(clippy::needless_pass_by_ref_mut)]
     pub fn do_battle(
         &mut self,
-        _attack_commands: Vec<AttackCommand>,
-        _defense_commands: Vec<DefenseCommand>,
-        _target: Coord,
+        attack_commands: Vec<AttackCommand>,
+        defense_commands: Vec<DefenseCommand>,
+        target: Coord,
+    ) -> Result<(), CommandError> {
+        self.do_command(Command::Battle {
+            attack_commands,
+            defense_commands,
+            target,
+        })
+    }
+
+    fn resolve_battle(
+        &mut self,
+        attack_commands: Vec<AttackCommand>,
+        defense_commands: Vec<DefenseCommand>,
+        target: Coord,
     ) -> Result<(), BattleError> {
-        Err(BattleError)
+        let defender = self.check_target(target)?;
+
+        if attack_commands.is_empty() {
+            return Err(BattleError::NoAttackers);
+        }
+
+        let mut board = self.board.clone();
+        let mut progressed = false;
+        let mut attackers = Vec::new();
+        // Summed wider than the u8 stats so that custom rules with strong units cannot overflow
+        let mut attack_power = 0_u16;
+        // Every coord whose tile may differ between the scratch board and the game's
+        let mut changed = vec![target];
+
+        for attack_command in attack_commands {
+            let position = match attack_command {
+                AttackCommand::Attack(coord) if !board.contains(coord) => {
+                    return Err(BattleError::OffBoard(coord));
+                }
+                AttackCommand::Attack(coord) => coord,
+                AttackCommand::MoveAttack(r#move) => {
+                    if attackers.contains(&r#move.from) {
+                        return Err(BattleError::AlreadyFighting(r#move.from));
+                    }
+
+                    self.check_move(&board, r#move)?;
+                    let Move { from, to } = r#move;
+
+                    board[to].piece_option = board[from].piece_option.take();
+                    progressed |= board[to].capture(self.cur_player);
+                    changed.extend([from, to]);
+                    to
+                }
+            };
+
+            let attacker = board[position]
+                .piece_option
+                .ok_or(BattleError::NoPiece(position))?;
+
+            if attacker.owner != self.cur_player {
+                return Err(BattleError::NotYourPiece(position));
+            }
+
+            if attackers.contains(&position) {
+                return Err(BattleError::AlreadyFighting(position));
+            }
+
+            if attacker.exhausted {
+                return Err(BattleError::PieceExhausted(position));
+            }
+
+            self.check_range(attacker, position, target)?;
+
+            attack_power += u16::from(self.rules[attacker.piece_type].power);
+            attackers.push(position);
+        }
+
+        let mut defense_power = u16::from(self.rules[defender.piece_type].power);
+        let mut supporters = Vec::new();
+        let mut retreat = None;
+
+        for defense_command in defense_commands {
+            match defense_command {
+                DefenseCommand::Defend(coord) => {
+                    if !board.contains(coord) {
+                        return Err(BattleError::OffBoard(coord));
+                    }
+
+                    let supporter = board[coord]
+                        .piece_option
+                        .ok_or(BattleError::NoPiece(coord))?;
+
+                    if supporter.owner != defender.owner {
+                        return Err(BattleError::NotDefendersPiece(coord));
+                    }
+
+                    if coord == target || supporters.contains(&coord) {
+                        return Err(BattleError::AlreadyFighting(coord));
+                    }
+
+                    self.check_range(supporter, coord, target)?;
+
+                    defense_power += u16::from(self.rules[supporter.piece_type].power);
+                    supporters.push(coord);
+                }
+                DefenseCommand::Retreat(r#move) => {
+                    if r#move.from != target {
+                        return Err(BattleError::NotTheTarget(r#move.from));
+                    }
+
+                    if retreat.is_some() {
+                        return Err(BattleError::MultipleRetreats);
+                    }
+
+                    self.check_reach(&board, r#move)?;
+                    retreat = Some(r#move.to);
+                    changed.push(r#move.to);
+                }
+            }
+        }
+
+        for attacker in attackers {
+            if let Some(piece) = &mut board[attacker].piece_option {
+                piece.exhausted = true;
+            }
+
+            changed.push(attacker);
+        }
+
+        if attack_power > defense_power {
+            board[target].piece_option = None;
+            progressed = true;
+
+            if let Some(to) = retreat {
+                board[to].piece_option = Some(Piece {
+                    exhausted: true,
+                    ..defender
+                });
+                board[to].capture(defender.owner);
+            }
+        }
+
+        for coord in changed {
+            self.set_tile(coord, board[coord]);
+        }
+
+        self.progressed |= progressed;
+
+        Ok(())
     }
 
+    /// Checks that `target` holds an enemy piece, returning it
+    fn check_target(&self, target: Coord) -> Result<Piece, BattleError> {
+        let defender = self
+            .board
+            .get(target)
+            .ok_or(BattleError::OffBoard(target))?
+            .piece_option
+            .ok_or(BattleError::NoTarget(target))?;
+
+        if defender.owner == self.cur_player {
+            return Err(BattleError::TargetIsYours(target));
+        }
+
+        Ok(defender)
+    }
+
+    /// Checks that `player` may move their piece along `move`
+    fn check_move(&self, board: &Board, r#move: Move) -> Result<Piece, MoveError> {
+        check_on_board(board, r#move)?;
+
+        let piece = board[r#move.from]
+            .piece_option
+            .ok_or(MoveError::NoPiece(r#move.from))?;
+
+        if piece.owner != self.cur_player {
+            return Err(MoveError::NotYourPiece(r#move.from));
+        }
+
+        if piece.exhausted {
+            return Err(MoveError::PieceExhausted(r#move.from));
+        }
+
+        self.check_reach(board, r#move)?;
+
+        Ok(piece)
+    }
+
+    /// Checks that the piece on `move.from` can reach `move.to`
+    fn check_reach(&self, board: &Board, r#move: Move) -> Result<(), MoveError> {
+        check_on_board(board, r#move)?;
+
+        let Move { from, to } = r#move;
+        let piece = board[from].piece_option.ok_or(MoveError::NoPiece(from))?;
+
+        if board[to].piece_option.is_some() {
+            return Err(MoveError::TileOccupied(to));
+        }
+
+        let distance = from.distance(to);
+
+        let speed = self.rules[piece.piece_type].speed;
+
+        if distance > u16::from(speed) {
+            return Err(MoveError::OutOfRange {
+                max: speed,
+                actual: distance,
+            });
+        }
+
+        if !board
+            .get_moves(from, &self.rules)
+            .is_some_and(|moves| moves.contains(&to))
+        {
+            return Err(MoveError::Blocked(to));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that `piece` standing on `position` can fight over `target`
+    fn check_range(&self, piece: Piece, position: Coord, target: Coord) -> Result<(), BattleError> {
+        let range = &self.rules[piece.piece_type].range;
+        if position.in_range(target, range) {
+            Ok(())
+        } else {
+            Err(BattleError::OutOfRange {
+                min: *range.start(),
+                max: *range.end(),
+                actual: position.distance(target),
+            })
+        }
+    }
+
+    /// Moves on through the phases until the next command phase, resolving their effects.
     ///
-    ///
-    /// # Arguments
-    ///
-    /// * `command`:
-    ///
-    /// returns: Result<(), `CommandError`>
-    ///
-    /// # Errors
-    ///
-    /// Will return Err if `command` is not possible
-    ///
-    /// # Examples
-    ///
-    /// ```
-    ///
-    /// ```
+    /// During the command phase this ends the current player's turn. During the upkeep phase the
+    /// next command phase is the current player's own, so this only finishes their upkeep.
     pub fn end_turn(mut self: Box<Self>) -> ActionOutcome {
-        self.do_resupply();
-        self.do_upkeep();
+        self.begin();
+
+        self.advance_phase();
 
-        ActionOutcome::Ongoing(self)
+        while self.phase != Phase::Command {
+            self.advance_phase();
+        }
+
+        self.record(Action::EndTurn);
+
+        self.outcome()
     }
 
-    fn do_upkeep(&mut self) {
-        *self.index_mut(self.cur_player) += u8::try_from(
-            self.board
+    /// Moves on to the next phase, resolving its effects
+    pub fn end_phase(mut self: Box<Self>) -> ActionOutcome {
+        self.begin();
+
+        self.advance_phase();
+        self.record(Action::EndPhase);
+
+        self.outcome()
+    }
+
+    fn advance_phase(&mut self) {
+        match self.phase {
+            Phase::Upkeep => {
+                // Positions are remembered at the start of each command phase, the game's first
+                // position included
+                self.set_phase(Phase::Command);
+                self.positions.push(self.hash);
+                self.log(Change::PopPosition);
+            }
+            Phase::Command => {
+                self.set_phase(Phase::Resupply);
+                self.do_resupply();
+            }
+            Phase::Resupply => {
+                self.set_cur_player(self.cur_player.opponent());
+                self.set_phase(Phase::Upkeep);
+                self.do_upkeep();
+
+                self.turn = self.turn.saturating_add(1);
+                self.turns_without_progress = if mem::take(&mut self.progressed) {
+                    0
+                } else {
+                    self.turns_without_progress.saturating_add(1)
+                };
+            }
+        }
+    }
+
+    fn outcome(self: Box<Self>) -> ActionOutcome {
+        match (self.has_won(Player::P1), self.has_won(Player::P2)) {
+            (true, true) => ActionOutcome::Draw,
+            (true, false) => ActionOutcome::Winner(Player::P1),
+            (false, true) => ActionOutcome::Winner(Player::P2),
+            (false, false) if self.is_draw() => ActionOutcome::Draw,
+            (false, false) => ActionOutcome::Ongoing(self),
+        }
+    }
+
+    fn has_won(&self, player: Player) -> bool {
+        let VictoryConditions {
+            elimination,
+            baseline_occupation,
+            income_threshold,
+            town_threshold,
+        } = self.rules.end_conditions.victory;
+        let opponent = player.opponent();
+
+        (elimination && self.is_eliminated(opponent))
+            || (baseline_occupation
+                && self.board.iter().any(|tile| {
+                    tile.produces_troops(opponent)
+                        && tile.piece_option.is_some_and(|piece| piece.owner == player)
+                }))
+            || income_threshold.is_some_and(|threshold| self.income(player) >= threshold)
+            || town_threshold.is_some_and(|threshold| {
+                self.board.towns_owned_by(player).len() >= usize::from(threshold)
+            })
+    }
+
+    /// A player is eliminated once they have no pieces left and cannot afford to purchase one,
+    /// even with the income from their next upkeep
+    fn is_eliminated(&self, player: Player) -> bool {
+        let money = self[player].saturating_add(self.income(player));
+
+        !self
+            .board
+            .iter()
+            .any(|tile| tile.piece_option.is_some_and(|piece| piece.owner == player))
+            && PieceType::ALL
                 .iter()
-                .filter(|tile| {
-                    tile.piece_option.is_some_and(|piece| {
-                        piece.piece_type == PieceType::Convoy && tile.gives_income()
-                    })
-                })
-                .count(),
-        )
-        .expect("There are no more than 256 towns")
-            + 2;
-
-        self.board
-            .iter_mut()
-            .filter_map(|tile| tile.piece_option.as_mut())
-            .for_each(|piece| {
-                if piece.owner == self.cur_player {
-                    piece.exhausted = false;
-                }
-            });
+                .all(|&piece_type| self.rules[piece_type].cost > money)
+    }
+
+    fn is_draw(&self) -> bool {
+        let draw = self.rules.end_conditions.draw;
+        let position = self.positions.last().copied();
+
+        draw.turn_limit.is_some_and(|limit| self.turn >= limit)
+            || draw
+                .no_progress_limit
+                .is_some_and(|limit| self.turns_without_progress >= limit)
+            || draw.repetition_limit.is_some_and(|limit| {
+                let repetitions = self
+                    .positions
+                    .iter()
+                    .filter(|&&hash| Some(hash) == position)
+                    .count();
+
+                repetitions >= usize::from(limit)
+            })
+    }
+
+    fn do_upkeep(&mut self) {
+        let income = self.income(self.cur_player);
+        self.set_money(
+            self.cur_player,
+            self[self.cur_player].saturating_add(income),
+        );
+
+        for coord in self.board.coords() {
+            if let Some(piece) = self.board[coord].piece_option
+                && piece.owner == self.cur_player
+            {
+                self.set_piece(
+                    coord,
+                    Some(Piece {
+                        exhausted: false,
+                        ..piece
+                    }),
+                );
+            }
+        }
     }
 
     fn do_resupply(&mut self) {
-        // TODO calculate logistics network
+        for coord in self.board.unsupplied_pieces(self.cur_player) {
+            if self.board[coord]
+                .piece_option
+                .is_some_and(|piece| self.rules[piece.piece_type].needs_supplies)
+            {
+                self.set_piece(coord, None);
+                self.progressed = true;
+            }
+        }
+    }
+}
 
-        self.cur_player = match self.cur_player {
-            Player::P1 => Player::P2,
-            Player::P2 => Player::P1,
-        };
+/// Checks that both ends of `move` are on `board`, so that indexing with them cannot panic
+fn check_on_board(board: &Board, Move { from, to }: Move) -> Result<(), MoveError> {
+    for coord in [from, to] {
+        if !board.contains(coord) {
+            return Err(MoveError::OffBoard(coord));
+        }
     }
+
+    Ok(())
 }
 
 impl Default for Game {
     fn default() -> Self {
-        Self {
-            board: Board::new(),
-            player_money: [3; 2],
-            cur_player: Player::P1,
-        }
+        *Self::with_rules(RuleSet::default())
+    }
+}
+
+/// Games are equal when their positions and rules are, however the positions were reached
+impl PartialEq for Game {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+            && self.board == other.board
+            && self.player_money == other.player_money
+            && self.cur_player == other.cur_player
+            && self.phase == other.phase
+            && self.rules == other.rules
     }
 }
 
+impl Eq for Game {}
+
 impl Index<Player> for Game {
     type Output = u8;
 
@@ -335,12 +883,3 @@ impl Index<Player> for Game {
         }
     }
 }
-
-impl Game {
-    const fn index_mut(&mut self, index: Player) -> &mut <Self as Index<Player>>::Output {
-        match index {
-            Player::P1 => &mut self.player_money[0],
-            Player::P2 => &mut self.player_money[1],
-        }
-    }
-}
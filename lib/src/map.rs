@@ -0,0 +1,304 @@
+//! A human-editable text format for boards.
+//!
+//! A map is an optional header of `key: value` lines closed by a `---` line, followed by one line
+//! per rank starting from the first. Within the header, `\\`, `\:`, `\n` and `\r` stand for a
+//! backslash, a colon, and line breaks, and whitespace around keys and values is ignored.
+//!
+//! Each rank lists its tiles separated by whitespace, a tile being written as the letter of its
+//! type, the same as in position strings (see [`TileType::letter`]), optionally followed by the
+//! letter of the piece standing on it (see [`Piece::letter`]). Pieces placed by a map start
+//! unexhausted.
+//!
+//! ```text
+//! name: Skirmish
+//! ---
+//! B BI B B
+//! . .  N .
+//! . N  . .
+//! b b  br b
+//! ```
+
+use crate::{
+    board::Board,
+    coord::Coord,
+    errors::{MapError, MapErrorKind},
+    pieces::Piece,
+    tile::{Tile, TileType},
+};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+const HEADER_END: &str = "---";
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Map {
+    /// The header's `key: value` pairs, in order
+    pub metadata: Vec<(String, String)>,
+    pub board: Board,
+}
+
+impl Map {
+    /// The value of the first header entry named `key`
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .iter()
+            .find_map(|(name, value)| (name == key).then_some(value.as_str()))
+    }
+}
+
+/// Errors point at the line, and the column where it helps, of the first mistake
+///
+/// ```
+/// use convoy::{map::Map, MapErrorKind};
+///
+/// let error = |text: &str| {
+///     let error = text.parse::<Map>().unwrap_err();
+///     (error.line, error.column, error.kind)
+/// };
+///
+/// assert_eq!(error("name Skirmish\n---\nB B\nb b"), (1, 1, MapErrorKind::MissingSeparator));
+/// assert_eq!(error("name: \\q\n---\nB B\nb b"), (1, 1, MapErrorKind::BadEscape));
+/// assert_eq!(error("B B\n.  X\nb b"), (2, 4, MapErrorKind::UnknownTile("X".to_owned())));
+/// assert_eq!(error("B B\nNIc\nb b"), (2, 1, MapErrorKind::UnknownTile("NIc".to_owned())));
+/// assert_eq!(
+///     error("name: Thin\n---\nB B\n.\nb b"),
+///     (4, 1, MapErrorKind::RaggedRank { expected: 2, actual: 1 })
+/// );
+/// assert_eq!(error("name: Short\n---\nB B"), (3, 1, MapErrorKind::BadSize));
+/// ```
+impl FromStr for Map {
+    type Err = MapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<(usize, &str)> = s
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line))
+            .collect();
+        let (header, grid) = lines
+            .iter()
+            .position(|(_, line)| line.trim() == HEADER_END)
+            .map_or((&lines[..0], &lines[..]), |end| {
+                (&lines[..end], &lines[end + 1..])
+            });
+
+        let metadata = header
+            .iter()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|&(line_number, line)| {
+                let error = |kind| MapError {
+                    line: line_number,
+                    column: 1,
+                    kind,
+                };
+                let (key, value) =
+                    split_header(line).ok_or_else(|| error(MapErrorKind::MissingSeparator))?;
+
+                unescape(key.trim())
+                    .zip(unescape(value.trim()))
+                    .ok_or_else(|| error(MapErrorKind::BadEscape))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let rows = grid
+            .iter()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|&(line_number, line)| Ok((line_number, parse_row(line_number, line)?)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            metadata,
+            board: build_board(&rows, grid.first().map_or(1, |&(line, _)| line))?,
+        })
+    }
+}
+
+fn parse_row(line_number: usize, line: &str) -> Result<Vec<Tile>, MapError> {
+    let mut tiles = Vec::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find(|char: char| !char.is_whitespace()) {
+        rest = &rest[start..];
+
+        let length = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let token = &rest[..length];
+
+        tiles.push(parse_tile(token).ok_or_else(|| MapError {
+            line: line_number,
+            column: line[..line.len() - rest.len()].chars().count() + 1,
+            kind: MapErrorKind::UnknownTile(token.to_owned()),
+        })?);
+
+        rest = &rest[length..];
+    }
+
+    Ok(tiles)
+}
+
+/// Splits a header line at its first unescaped `:`
+fn split_header(line: &str) -> Option<(&str, &str)> {
+    let mut escaped = false;
+
+    for (index, char) in line.char_indices() {
+        match char {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ':' => return Some((&line[..index], &line[index + 1..])),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Escapes everything which would otherwise end a header key or line
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for char in text.chars() {
+        match char {
+            '\\' => escaped.push_str("\\\\"),
+            ':' => escaped.push_str("\\:"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(char),
+        }
+    }
+
+    escaped
+}
+
+/// The inverse of [`escape`], or None if `text` has an unknown or unfinished escape
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(char) = chars.next() {
+        unescaped.push(match char {
+            '\\' => match chars.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                escaped @ ('\\' | ':') => escaped,
+                _ => return None,
+            },
+            _ => char,
+        });
+    }
+
+    Some(unescaped)
+}
+
+fn parse_tile(token: &str) -> Option<Tile> {
+    let mut rest = token.chars();
+    let tile_type = TileType::from_letter(rest.next()?)?;
+    let piece_option = match rest.next() {
+        None => None,
+        Some(letter) => Some(Piece::from_letter(letter)?),
+    };
+
+    rest.next().is_none().then_some(Tile {
+        piece_option,
+        tile_type,
+    })
+}
+
+/// Builds a board from each rank's tiles along with the line it was written on
+fn build_board(rows: &[(usize, Vec<Tile>)], first_line: usize) -> Result<Board, MapError> {
+    let size_error = MapError {
+        line: first_line,
+        column: 1,
+        kind: MapErrorKind::BadSize,
+    };
+    let ranks = u8::try_from(rows.len()).map_err(|_| size_error.clone())?;
+    let files = u8::try_from(rows.first().map_or(0, |(_, row)| row.len()))
+        .map_err(|_| size_error.clone())?;
+    let mut board = Board::with_size(ranks, files).ok_or(size_error)?;
+
+    for (rank, (line, row)) in rows.iter().enumerate() {
+        if row.len() != usize::from(files) {
+            return Err(MapError {
+                line: *line,
+                column: 1,
+                kind: MapErrorKind::RaggedRank {
+                    expected: files,
+                    actual: row.len(),
+                },
+            });
+        }
+
+        for (file, &tile) in row.iter().enumerate() {
+            let coord = Coord::new(rank, file).expect("Ranks and files fit in a u8");
+            board[coord] = tile;
+        }
+    }
+
+    Ok(board)
+}
+
+/// Writes a map which reads back as the same map, other than pieces being unexhausted and
+/// whitespace around header keys and values being dropped
+///
+/// ```
+/// use convoy::map::Map;
+///
+/// let text = r"name: Skirmish
+/// odd\: key: first line\nsecond \\ line
+/// ---
+/// B  BI B  B
+/// .  .  N  T
+/// .  t  .  .
+/// b  b  br b
+/// ";
+/// let map: Map = text.parse().unwrap();
+///
+/// assert_eq!(map.get("odd: key"), Some("first line\nsecond \\ line"));
+/// assert_eq!(map.to_string(), text);
+///
+/// let mut odd = map.clone();
+/// odd.metadata = vec![("a:b\\c".to_owned(), "d\r\ne: f".to_owned())];
+/// assert_eq!(odd.to_string().parse(), Ok(odd));
+/// ```
+impl Display for Map {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.metadata.is_empty() {
+            for (key, value) in &self.metadata {
+                writeln!(f, "{}: {}", escape(key), escape(value))?;
+            }
+
+            writeln!(f, "{HEADER_END}")?;
+        }
+
+        let tokens: Vec<Vec<String>> = self
+            .board
+            .rows()
+            .map(|row| row.iter().map(|&tile| tile_token(tile)).collect())
+            .collect();
+        let width = tokens.iter().flatten().map(String::len).max().unwrap_or(0);
+
+        for row in tokens {
+            let line = row
+                .iter()
+                .map(|token| format!("{token:width$}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+fn tile_token(tile: Tile) -> String {
+    [Some(tile.tile_type.letter()), tile.piece_option.map(Piece::letter)]
+        .into_iter()
+        .flatten()
+        .collect()
+}
@@ -0,0 +1,171 @@
+//! A compact text notation for actions.
+//!
+//! - A move is written as two coords joined by a dash: `b3-d3`
+//! - A purchase is the piece letter, `@` and the coord: `I@a5`
+//! - A battle lists its attackers, then `>` and the target, then optionally `/` and the defenders.
+//!   Each attacker or defender is either a coord, or a move for move-attacks and retreats:
+//!   `c4,b3-b4>a4/c5,a4-a3`
+//! - Ending the phase is `next` and ending the turn is `end`
+//!
+//! ```
+//! use convoy::Action;
+//!
+//! for notation in ["b3-d3", "I@a5", "c4,b3-b4>a4/c5,a4-a3", "c4>a4", "next", "end"] {
+//!     let action: Action = notation.parse().expect("The notation is valid");
+//!
+//!     assert_eq!(action.to_string(), notation);
+//! }
+//! ```
+
+use crate::{
+    coord::{Coord, Move},
+    errors::ParseActionError,
+    pieces::PieceType,
+    Action, AttackCommand, Command, DefenseCommand,
+};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Command(command) => write!(f, "{command}"),
+            Self::EndPhase => write!(f, "next"),
+            Self::EndTurn => write!(f, "end"),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = ParseActionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "next" => Ok(Self::EndPhase),
+            "end" => Ok(Self::EndTurn),
+            _ => Ok(Self::Command(s.parse()?)),
+        }
+    }
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Move(r#move) => write!(f, "{move}"),
+            Self::Purchase(piece_type, coord) => write!(f, "{piece_type}@{coord}"),
+            Self::Battle {
+                attack_commands,
+                defense_commands,
+                target,
+            } => {
+                write!(f, "{}>{target}", join(attack_commands))?;
+
+                if defense_commands.is_empty() {
+                    Ok(())
+                } else {
+                    write!(f, "/{}", join(defense_commands))
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = ParseActionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((piece_type, coord)) = s.split_once('@') {
+            let mut letters = piece_type.chars();
+            let piece_type = letters
+                .next()
+                .filter(|_| letters.next().is_none())
+                .and_then(PieceType::from_letter)
+                .ok_or_else(|| ParseActionError::UnknownPiece(piece_type.to_owned()))?;
+
+            return Ok(Self::Purchase(piece_type, coord.parse()?));
+        }
+
+        if let Some((attack_commands, rest)) = s.split_once('>') {
+            let (target, defense_commands) = rest.split_once('/').unwrap_or((rest, ""));
+
+            return Ok(Self::Battle {
+                attack_commands: split(attack_commands)?,
+                defense_commands: split(defense_commands)?,
+                target: target.parse()?,
+            });
+        }
+
+        if s.contains('-') {
+            return Ok(Self::Move(s.parse()?));
+        }
+
+        Err(ParseActionError::Unrecognised(s.to_owned()))
+    }
+}
+
+impl Display for AttackCommand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Attack(coord) => write!(f, "{coord}"),
+            Self::MoveAttack(r#move) => write!(f, "{move}"),
+        }
+    }
+}
+
+impl FromStr for AttackCommand {
+    type Err = ParseActionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match coord_or_move(s)? {
+            Ok(coord) => Self::Attack(coord),
+            Err(r#move) => Self::MoveAttack(r#move),
+        })
+    }
+}
+
+impl Display for DefenseCommand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Defend(coord) => write!(f, "{coord}"),
+            Self::Retreat(r#move) => write!(f, "{move}"),
+        }
+    }
+}
+
+impl FromStr for DefenseCommand {
+    type Err = ParseActionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match coord_or_move(s)? {
+            Ok(coord) => Self::Defend(coord),
+            Err(r#move) => Self::Retreat(r#move),
+        })
+    }
+}
+
+/// A coord, or a move if `s` contains a dash
+fn coord_or_move(s: &str) -> Result<Result<Coord, Move>, ParseActionError> {
+    if s.contains('-') {
+        Ok(Err(s.parse()?))
+    } else {
+        Ok(Ok(s.parse()?))
+    }
+}
+
+fn join(items: &[impl Display]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn split<T: FromStr<Err = ParseActionError>>(s: &str) -> Result<Vec<T>, ParseActionError> {
+    if s.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    s.split(',').map(str::parse).collect()
+}
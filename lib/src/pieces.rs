@@ -1,4 +1,4 @@
-use crate::Player;
+use crate::{rules::RuleSet, Player};
 use std::{
     fmt::{Debug, Display, Formatter},
     ops::{Deref, RangeInclusive},
@@ -7,7 +7,7 @@ use std::{
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PieceType {
     Artillery,
@@ -18,58 +18,65 @@ pub enum PieceType {
 
 impl Display for PieceType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::Artillery => "A",
-                Self::Convoy => "C",
-                Self::Infantry => "I",
-                Self::Recon => "R",
-            }
-        )
+        write!(f, "{}", self.letter())
     }
 }
 
 impl PieceType {
+    pub const ALL: [Self; 4] = [Self::Artillery, Self::Convoy, Self::Infantry, Self::Recon];
+
     #[must_use]
-    pub const fn cost(&self) -> u8 {
+    pub const fn letter(self) -> char {
         match self {
-            Self::Convoy => 3,
-            Self::Infantry => 2,
-            Self::Recon | Self::Artillery => 4,
+            Self::Artillery => 'A',
+            Self::Convoy => 'C',
+            Self::Infantry => 'I',
+            Self::Recon => 'R',
         }
     }
 
+    /// The inverse of [`PieceType::letter`]
     #[must_use]
-    pub const fn power(&self) -> u8 {
-        match self {
-            Self::Convoy => 0,
-            Self::Infantry | Self::Artillery => 2,
-            Self::Recon => 1,
+    pub const fn from_letter(letter: char) -> Option<Self> {
+        match letter {
+            'A' => Some(Self::Artillery),
+            'C' => Some(Self::Convoy),
+            'I' => Some(Self::Infantry),
+            'R' => Some(Self::Recon),
+            _ => None,
         }
     }
 
+    /// The cost under the default rules
     #[must_use]
-    pub const fn range(&self) -> RangeInclusive<u8> {
-        match self {
-            Self::Artillery => 2..=3,
-            Self::Convoy => 0..=0,
-            Self::Infantry | Self::Recon => 1..=1,
-        }
+    #[deprecated = "Read the unit's stats from the game's `RuleSet` instead"]
+    pub fn cost(&self) -> u8 {
+        RuleSet::default()[*self].cost
     }
 
+    /// The power under the default rules
     #[must_use]
-    pub const fn speed(&self) -> u8 {
-        match self {
-            Self::Convoy => 3,
-            Self::Infantry | Self::Artillery => 2,
-            Self::Recon => 4,
-        }
+    #[deprecated = "Read the unit's stats from the game's `RuleSet` instead"]
+    pub fn power(&self) -> u8 {
+        RuleSet::default()[*self].power
+    }
+
+    /// The range under the default rules
+    #[must_use]
+    #[deprecated = "Read the unit's stats from the game's `RuleSet` instead"]
+    pub fn range(&self) -> RangeInclusive<u8> {
+        RuleSet::default()[*self].range.clone()
+    }
+
+    /// The speed under the default rules
+    #[must_use]
+    #[deprecated = "Read the unit's stats from the game's `RuleSet` instead"]
+    pub fn speed(&self) -> u8 {
+        RuleSet::default()[*self].speed
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Piece {
     pub owner: Player,
@@ -94,4 +101,33 @@ impl Piece {
             piece_type,
         }
     }
+
+    /// The piece type's letter, uppercase for [`Player::P1`] and lowercase for [`Player::P2`]
+    #[must_use]
+    pub const fn letter(self) -> char {
+        let letter = self.piece_type.letter();
+
+        match self.owner {
+            Player::P1 => letter,
+            Player::P2 => letter.to_ascii_lowercase(),
+        }
+    }
+
+    /// The unexhausted piece written as `letter`, the inverse of [`Piece::letter`]
+    #[must_use]
+    pub const fn from_letter(letter: char) -> Option<Self> {
+        let Some(piece_type) = PieceType::from_letter(letter.to_ascii_uppercase()) else {
+            return None;
+        };
+
+        Some(Self {
+            owner: if letter.is_ascii_uppercase() {
+                Player::P1
+            } else {
+                Player::P2
+            },
+            exhausted: false,
+            piece_type,
+        })
+    }
 }
@@ -0,0 +1,354 @@
+use crate::{
+    board::Board,
+    coord::Coord,
+    errors::PositionError,
+    pieces::Piece,
+    rules::RuleSet,
+    tile::{Tile, TileType},
+    Game, Phase, Player,
+};
+use std::fmt::Write;
+
+const FIELDS: usize = 7;
+
+impl Game {
+    /// The game's position on a single line, made of space-separated fields:
+    ///
+    /// 1. The tiles, rank by rank separated by `/`. Each tile is its [`TileType::letter`], and a
+    ///    number before a tile repeats it.
+    /// 2. The pieces, laid out the same way. Each piece is its letter, uppercase for
+    ///    [`Player::P1`] and lowercase for [`Player::P2`], followed by `'` if it is exhausted. A
+    ///    number stands for that many empty tiles.
+    /// 3. The current player, `1` or `2`
+    /// 4. The phase, `U`, `C` or `R`
+    /// 5. Each player's money, separated by `,`
+    /// 6. The turn
+    /// 7. The turns without progress
+    ///
+    /// The history and the rules are not included.
+    ///
+    /// ```
+    /// use convoy::{board::Board, rules::RuleSet, Game};
+    ///
+    /// let board = Board::with_size(4, 3).expect("The size is valid");
+    /// let game = Game::with_board(board, RuleSet::default());
+    /// let position = game.to_position_string();
+    ///
+    /// assert_eq!(position, "3B/3./3./3b 3/3/3/3 1 C 3,3 0 0");
+    /// let parsed = Game::from_position_string(&position, RuleSet::default());
+    ///
+    /// assert_eq!(parsed.map(|game| game.to_position_string()), Ok(position));
+    /// ```
+    #[must_use]
+    pub fn to_position_string(&self) -> String {
+        let tiles = self
+            .board
+            .rows()
+            .map(|row| {
+                let mut rank = String::new();
+
+                for (count, letter) in runs(row.iter().map(|tile| tile.tile_type.letter())) {
+                    if count > 1 {
+                        write!(rank, "{count}").expect("Writing to a String never fails");
+                    }
+                    rank.push(letter);
+                }
+
+                rank
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let pieces = self
+            .board
+            .rows()
+            .map(|row| {
+                let mut rank = String::new();
+                let mut empty = 0;
+
+                for tile in row {
+                    let Some(piece) = tile.piece_option else {
+                        empty += 1;
+                        continue;
+                    };
+
+                    if empty > 0 {
+                        write!(rank, "{empty}").expect("Writing to a String never fails");
+                        empty = 0;
+                    }
+
+                    rank.push(piece.letter());
+
+                    if piece.exhausted {
+                        rank.push('\'');
+                    }
+                }
+
+                if empty > 0 {
+                    write!(rank, "{empty}").expect("Writing to a String never fails");
+                }
+
+                rank
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let [p1_money, p2_money] = self.player_money;
+
+        format!(
+            "{tiles} {pieces} {} {} {p1_money},{p2_money} {} {}",
+            match self.cur_player {
+                Player::P1 => '1',
+                Player::P2 => '2',
+            },
+            match self.phase {
+                Phase::Upkeep => 'U',
+                Phase::Command => 'C',
+                Phase::Resupply => 'R',
+            },
+            self.turn,
+            self.turns_without_progress,
+        )
+    }
+
+    /// The inverse of [`Game::to_position_string`], played with `rules` and with an empty history
+    ///
+    /// # Errors
+    ///
+    /// Will return Err if `position` is not a valid position string
+    ///
+    /// ```
+    /// use convoy::{rules::RuleSet, Game, PositionError};
+    ///
+    /// let parse = |position| Game::from_position_string(position, RuleSet::default()).err();
+    ///
+    /// // Counts are checked against the widest board and then the first rank's width
+    /// assert_eq!(
+    ///     parse("99999999999999999999999B/3./3./3b 3/3/3/3 1 C 3,3 0 0"),
+    ///     Some(PositionError::RankTooLong { rank: 0, max: 255 })
+    /// );
+    /// assert_eq!(
+    ///     parse("3B/200./3./3b 3/3/3/3 1 C 3,3 0 0"),
+    ///     Some(PositionError::RankTooLong { rank: 1, max: 3 })
+    /// );
+    /// assert_eq!(
+    ///     parse("3B/3./3./3b 3/3/3/99999 1 C 3,3 0 0"),
+    ///     Some(PositionError::RankTooLong { rank: 3, max: 3 })
+    /// );
+    /// assert_eq!(parse("3B/3./3./3b 3/3/3/2 1 C 3,3 0 0"), Some(PositionError::RaggedRank {
+    ///     rank: 3,
+    ///     expected: 3,
+    ///     actual: 2,
+    /// }));
+    /// ```
+    pub fn from_position_string(
+        position: &str,
+        rules: RuleSet,
+    ) -> Result<Box<Self>, PositionError> {
+        let fields: Vec<&str> = position.split_whitespace().collect();
+        let &[
+            tiles,
+            pieces,
+            player,
+            phase,
+            money,
+            turn,
+            turns_without_progress,
+        ] = &fields[..]
+        else {
+            return Err(PositionError::FieldCount {
+                expected: FIELDS,
+                actual: fields.len(),
+            });
+        };
+
+        let mut game = Self::with_board(parse_board(tiles, pieces)?, rules);
+
+        game.cur_player = match player {
+            "1" => Player::P1,
+            "2" => Player::P2,
+            _ => return Err(PositionError::UnknownPlayer(player.to_owned())),
+        };
+        game.phase = match phase {
+            "U" => Phase::Upkeep,
+            "C" => Phase::Command,
+            "R" => Phase::Resupply,
+            _ => return Err(PositionError::UnknownPhase(phase.to_owned())),
+        };
+
+        let (p1_money, p2_money) = money
+            .split_once(',')
+            .ok_or_else(|| PositionError::BadNumber(money.to_owned()))?;
+        game.player_money = [parse_number(p1_money)?, parse_number(p2_money)?];
+        game.turn = parse_number(turn)?;
+        game.turns_without_progress = parse_number(turns_without_progress)?;
+        game.start();
+
+        Ok(game)
+    }
+}
+
+fn parse_board(tiles: &str, pieces: &str) -> Result<Board, PositionError> {
+    let max_files = usize::from(u8::MAX);
+
+    if tiles.split('/').count() > usize::from(u8::MAX) {
+        return Err(PositionError::BadSize);
+    }
+
+    // The first rank sets the width which every other rank is held to
+    let mut tile_ranks: Vec<Vec<TileType>> = Vec::new();
+
+    for (rank, tile_types) in tiles.split('/').enumerate() {
+        let max = tile_ranks.first().map_or(max_files, Vec::len);
+        tile_ranks.push(parse_tile_rank(rank, tile_types, max)?);
+    }
+
+    let files = tile_ranks.first().map_or(0, Vec::len);
+    let piece_ranks = pieces
+        .split('/')
+        .enumerate()
+        .map(|(rank, pieces)| parse_piece_rank(rank, pieces, files))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut board = u8::try_from(tile_ranks.len())
+        .ok()
+        .zip(u8::try_from(files).ok())
+        .and_then(|(ranks, files)| Board::with_size(ranks, files))
+        .ok_or(PositionError::BadSize)?;
+
+    if piece_ranks.len() != tile_ranks.len() {
+        return Err(PositionError::BadSize);
+    }
+
+    for (rank, (tile_types, pieces)) in tile_ranks.into_iter().zip(piece_ranks).enumerate() {
+        for (tile_count, expected) in [(tile_types.len(), files), (pieces.len(), files)] {
+            if tile_count != expected {
+                return Err(PositionError::RaggedRank {
+                    rank,
+                    expected,
+                    actual: tile_count,
+                });
+            }
+        }
+
+        for (file, (tile_type, piece_option)) in tile_types.into_iter().zip(pieces).enumerate() {
+            let coord = Coord::new(rank, file).expect("The board's size fits in a u8");
+
+            board[coord] = Tile {
+                piece_option,
+                tile_type,
+            };
+        }
+    }
+
+    Ok(board)
+}
+
+/// Parses the tiles of a rank, which may not hold more than `max` of them
+fn parse_tile_rank(index: usize, rank: &str, max: usize) -> Result<Vec<TileType>, PositionError> {
+    let mut tile_types = Vec::new();
+    let mut count = None;
+
+    for letter in rank.chars() {
+        if let Some(digit) = letter.to_digit(10) {
+            count = Some(add_digit(count, digit, index, max)?);
+            continue;
+        }
+
+        let tile_type = TileType::from_letter(letter).ok_or(PositionError::UnknownTile(letter))?;
+
+        extend_rank(&mut tile_types, count.take().unwrap_or(1), tile_type, index, max)?;
+    }
+
+    if count.is_some() {
+        return Err(PositionError::DanglingCount(rank.to_owned()));
+    }
+
+    Ok(tile_types)
+}
+
+/// Parses the pieces of a rank, which may not cover more than `max` tiles
+fn parse_piece_rank(
+    index: usize,
+    rank: &str,
+    max: usize,
+) -> Result<Vec<Option<Piece>>, PositionError> {
+    let mut pieces: Vec<Option<Piece>> = Vec::new();
+    let mut empty = None;
+
+    for letter in rank.chars() {
+        if let Some(digit) = letter.to_digit(10) {
+            empty = Some(add_digit(empty, digit, index, max)?);
+            continue;
+        }
+
+        extend_rank(&mut pieces, empty.take().unwrap_or(0), None, index, max)?;
+
+        if letter == '\'' {
+            match pieces.last_mut() {
+                Some(Some(piece)) if !piece.exhausted => piece.exhausted = true,
+                _ => return Err(PositionError::UnknownPiece(letter)),
+            }
+            continue;
+        }
+
+        let piece = Piece::from_letter(letter).ok_or(PositionError::UnknownPiece(letter))?;
+        extend_rank(&mut pieces, 1, Some(piece), index, max)?;
+    }
+
+    extend_rank(&mut pieces, empty.unwrap_or(0), None, index, max)?;
+
+    Ok(pieces)
+}
+
+/// Appends `digit` to a run-length count, which can never usefully exceed `max`
+fn add_digit(
+    count: Option<usize>,
+    digit: u32,
+    rank: usize,
+    max: usize,
+) -> Result<usize, PositionError> {
+    count
+        .unwrap_or(0)
+        .checked_mul(10)
+        .and_then(|count| count.checked_add(usize::try_from(digit).ok()?))
+        .filter(|&count| count <= max)
+        .ok_or(PositionError::RankTooLong { rank, max })
+}
+
+/// Adds a run of `count` copies of `item` to a rank, unless it would hold more than `max` items
+fn extend_rank<T: Copy>(
+    items: &mut Vec<T>,
+    count: usize,
+    item: T,
+    rank: usize,
+    max: usize,
+) -> Result<(), PositionError> {
+    if items.len().saturating_add(count) > max {
+        return Err(PositionError::RankTooLong { rank, max });
+    }
+
+    items.extend((0..count).map(|_| item));
+
+    Ok(())
+}
+
+fn parse_number<T: std::str::FromStr>(number: &str) -> Result<T, PositionError> {
+    number
+        .parse()
+        .map_err(|_| PositionError::BadNumber(number.to_owned()))
+}
+
+/// Each run of equal items, with its length
+fn runs<T: PartialEq>(items: impl Iterator<Item = T>) -> Vec<(usize, T)> {
+    let mut runs: Vec<(usize, T)> = Vec::new();
+
+    for item in items {
+        match runs.last_mut() {
+            Some((count, last)) if *last == item => *count += 1,
+            _ => runs.push((1, item)),
+        }
+    }
+
+    runs
+}
@@ -0,0 +1,279 @@
+//! A text format for archiving whole games.
+//!
+//! A record starts with header tags written as `[Key "value"]`, one per line, followed by the
+//! actions in [action notation](crate::Action) grouped into numbered turns, each turn ending with
+//! `end`. The usual tags are `P1`, `P2`, `Date`, `Map`, `RuleSet`, `Position` and `Result`, the
+//! position being the [position string](Game::to_position_string) the game started from and the
+//! result being `1-0`, `0-1`, `1/2-1/2` or `*` while the game is ongoing. Within tag values, `\`
+//! escapes the next character, with `\n` and `\r` standing for line breaks.
+//!
+//! ```
+//! use convoy::{record::Record, rules::RuleSet};
+//!
+//! let record: Record = "[P1 \"Alice\"]\n[Result \"*\"]\n\n1. end\n2. end\n"
+//!     .parse()
+//!     .expect("The record is valid");
+//!
+//! assert_eq!(record.get("P1"), Some("Alice"));
+//! assert_eq!(record.to_string().parse(), Ok(record.clone()));
+//! assert!(record.replay(RuleSet::default()).is_ok());
+//! ```
+
+use crate::{
+    errors::{RecordError, RecordErrorKind, ReplayError},
+    rules::RuleSet,
+    Action, ActionOutcome, Game, Player,
+};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Record {
+    /// The header tags, in order
+    pub tags: Vec<(String, String)>,
+    pub actions: Vec<Action>,
+}
+
+impl Record {
+    /// A record of every action taken in `game`, tagged with the position it started from
+    ///
+    /// ```
+    /// use convoy::{record::Record, rules::RuleSet, ActionOutcome, Game};
+    ///
+    /// let start = "3B/2.N/3./3b I2/3/3/2i 1 C 3,3 0 0";
+    /// let mut game = Game::from_position_string(start, RuleSet::default()).unwrap();
+    ///
+    /// for action in ["a0-b1", "end", "d2-c2", "end"] {
+    ///     let Ok(ActionOutcome::Ongoing(next)) = game.take_action(action.parse().unwrap()) else {
+    ///         panic!("The actions are legal");
+    ///     };
+    ///     game = next;
+    /// }
+    ///
+    /// let mut record = Record::from_game(&game);
+    /// record.set("P1", "Alice \"Ace\" [1]\nReserve");
+    /// let record: Record = record.to_string().parse().unwrap();
+    ///
+    /// assert_eq!(record.get("Position"), Some(start));
+    /// assert_eq!(record.get("P1"), Some("Alice \"Ace\" [1]\nReserve"));
+    ///
+    /// let Ok(ActionOutcome::Ongoing(replayed)) = record.replay(RuleSet::default()) else {
+    ///     panic!("The record replays");
+    /// };
+    /// assert_eq!(replayed.to_position_string(), game.to_position_string());
+    /// ```
+    #[must_use]
+    pub fn from_game(game: &Game) -> Self {
+        Self {
+            tags: vec![("Position".to_owned(), game.start_position().to_owned())],
+            actions: game.history().cloned().collect(),
+        }
+    }
+
+    /// The value of the first tag named `key`
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find_map(|(name, value)| (name == key).then_some(value.as_str()))
+    }
+
+    /// Sets the first tag named `key`, adding it if there is none
+    pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        let value = value.into();
+
+        match self.tags.iter_mut().find(|(name, _)| name == key) {
+            Some((_, old)) => *old = value,
+            None => self.tags.push((key.to_owned(), value)),
+        }
+    }
+
+    /// Sets the `Result` tag from how the game went
+    pub fn set_result(&mut self, outcome: &ActionOutcome) {
+        let result = match outcome {
+            ActionOutcome::Ongoing(_) => "*",
+            ActionOutcome::Draw => "1/2-1/2",
+            ActionOutcome::Winner(Player::P1) => "1-0",
+            ActionOutcome::Winner(Player::P2) => "0-1",
+        };
+
+        self.set("Result", result);
+    }
+
+    /// Takes every recorded action in turn, played with `rules` and starting from the `Position`
+    /// tag, or from the default board without one
+    ///
+    /// # Errors
+    ///
+    /// Will return Err if the `Position` tag is invalid, an action is rejected, or the game ends
+    /// before every action was taken
+    pub fn replay(&self, rules: RuleSet) -> Result<ActionOutcome, ReplayError> {
+        let game = match self.get("Position") {
+            Some(position) => Game::from_position_string(position, rules)?,
+            None => Game::with_rules(rules),
+        };
+        let mut outcome = ActionOutcome::Ongoing(game);
+
+        for (index, action) in self.actions.iter().enumerate() {
+            let ActionOutcome::Ongoing(game) = outcome else {
+                return Err(ReplayError::GameOver { index });
+            };
+
+            outcome = game
+                .take_action(action.clone())
+                .map_err(|(_, error)| ReplayError::Rejected { index, error })?;
+        }
+
+        Ok(outcome)
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (key, value) in &self.tags {
+            writeln!(f, "[{key} \"{}\"]", escape(value))?;
+        }
+
+        if !self.tags.is_empty() {
+            writeln!(f)?;
+        }
+
+        let mut turn = 1;
+        let mut line_start = true;
+
+        for action in &self.actions {
+            if line_start {
+                write!(f, "{turn}.")?;
+                line_start = false;
+            }
+
+            write!(f, " {action}")?;
+
+            if *action == Action::EndTurn {
+                writeln!(f)?;
+                turn += 1;
+                line_start = true;
+            }
+        }
+
+        if line_start { Ok(()) } else { writeln!(f) }
+    }
+}
+
+impl FromStr for Record {
+    type Err = RecordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut record = Self::default();
+        let mut turn = 1;
+
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            let error = |column, kind| RecordError {
+                line: line_number,
+                column,
+                kind,
+            };
+
+            if line.trim_start().starts_with('[') {
+                if !record.actions.is_empty() {
+                    return Err(error(1, RecordErrorKind::LateTag));
+                }
+
+                record
+                    .tags
+                    .push(parse_tag(line).ok_or_else(|| error(1, RecordErrorKind::BadTag))?);
+                continue;
+            }
+
+            for (column, token) in tokens(line) {
+                if let Some(number) = token.strip_suffix('.') {
+                    if number.parse() != Ok(turn) {
+                        return Err(error(
+                            column,
+                            RecordErrorKind::TurnNumber {
+                                expected: turn,
+                                actual: number.to_owned(),
+                            },
+                        ));
+                    }
+
+                    continue;
+                }
+
+                let action: Action = token
+                    .parse()
+                    .map_err(|kind| error(column, RecordErrorKind::Action(kind)))?;
+
+                if action == Action::EndTurn {
+                    turn += 1;
+                }
+
+                record.actions.push(action);
+            }
+        }
+
+        Ok(record)
+    }
+}
+
+/// Parses `[Key "value"]`
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+    let (key, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => unescaped.push(match chars.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                escaped => escaped,
+            }),
+            '"' | ']' => return None,
+            _ => unescaped.push(char),
+        }
+    }
+
+    Some((key.to_owned(), unescaped))
+}
+
+/// Escapes everything which would otherwise end a tag value or its line
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for char in value.chars() {
+        match char {
+            '\\' | '"' | ']' => {
+                escaped.push('\\');
+                escaped.push(char);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(char),
+        }
+    }
+
+    escaped
+}
+
+/// The whitespace-separated tokens of `line`, each with its column
+fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split(char::is_whitespace)
+        .scan(1, |column, token| {
+            let start = *column;
+            *column += token.chars().count() + 1;
+
+            Some((start, token))
+        })
+        .filter(|(_, token)| !token.is_empty())
+}
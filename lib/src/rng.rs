@@ -0,0 +1,61 @@
+//! A small deterministic random number generator.
+//!
+//! Its output only depends on the seed, on every platform and across versions of this crate, so
+//! anything generated from a seed can be shared by that seed alone.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// `SplitMix64`, see <https://prng.di.unimi.it/splitmix64.c>
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub const fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed number in `0..bound`
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `bound` is 0
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "The bound must be positive");
+
+        let bound = u64::try_from(bound).expect("usize fits in a u64");
+        let threshold = bound.wrapping_neg() % bound;
+
+        loop {
+            let product = u128::from(self.next_u64()) * u128::from(bound);
+
+            #[allow(clippy::cast_possible_truncation)]
+            if product as u64 >= threshold {
+                return usize::try_from(product >> 64).expect("The result is below a usize");
+            }
+        }
+    }
+
+    /// A uniformly chosen element of `slice`, or None if it is empty
+    pub fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        (!slice.is_empty()).then(|| &slice[self.below(slice.len())])
+    }
+
+    /// True `percent` times out of a hundred
+    pub fn percent(&mut self, percent: u8) -> bool {
+        self.below(100) < usize::from(percent)
+    }
+}
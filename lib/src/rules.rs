@@ -0,0 +1,226 @@
+use crate::pieces::PieceType;
+use std::ops::{Index, RangeInclusive};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Every number the game is balanced around, along with how it ends and what can be undone
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RuleSet {
+    pub artillery: UnitStats,
+    pub convoy: UnitStats,
+    pub infantry: UnitStats,
+    pub recon: UnitStats,
+    pub starting_money: u8,
+    /// Paid every upkeep regardless of towns
+    pub base_income: u8,
+    /// Paid every upkeep for each town connected to the logistics network
+    pub town_income: u8,
+    pub end_conditions: EndConditions,
+    pub undo_policy: UndoPolicy,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self {
+            artillery: UnitStats {
+                cost: 4,
+                power: 2,
+                range: 2..=3,
+                speed: 2,
+                needs_supplies: true,
+            },
+            convoy: UnitStats {
+                cost: 3,
+                power: 0,
+                range: 0..=0,
+                speed: 3,
+                needs_supplies: true,
+            },
+            infantry: UnitStats {
+                cost: 2,
+                power: 2,
+                range: 1..=1,
+                speed: 2,
+                needs_supplies: true,
+            },
+            recon: UnitStats {
+                cost: 4,
+                power: 1,
+                range: 1..=1,
+                speed: 4,
+                needs_supplies: false,
+            },
+            starting_money: 3,
+            base_income: 2,
+            town_income: 1,
+            end_conditions: EndConditions::default(),
+            undo_policy: UndoPolicy::default(),
+        }
+    }
+}
+
+impl Index<PieceType> for RuleSet {
+    type Output = UnitStats;
+
+    fn index(&self, index: PieceType) -> &Self::Output {
+        match index {
+            PieceType::Artillery => &self.artillery,
+            PieceType::Convoy => &self.convoy,
+            PieceType::Infantry => &self.infantry,
+            PieceType::Recon => &self.recon,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UnitStats {
+    pub cost: u8,
+    /// Added to the side of any battle the unit takes part in
+    pub power: u8,
+    /// The distances from which the unit can take part in a battle
+    pub range: RangeInclusive<u8>,
+    /// The number of orthogonal steps the unit can take in one move
+    pub speed: u8,
+    /// Whether the unit is destroyed when left outside the logistics network
+    pub needs_supplies: bool,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EndConditions {
+    pub victory: VictoryConditions,
+    pub draw: DrawConditions,
+}
+
+/// The ways to win, checked after every action
+///
+/// ```
+/// use convoy::{rules::RuleSet, Action, ActionOutcome, Game, Player};
+///
+/// let outcome = |position, rules, action: &str| {
+///     let game = Game::from_position_string(position, rules).unwrap();
+///     let action: Action = action.parse().unwrap();
+///     game.take_action(action).unwrap()
+/// };
+/// let winner = ActionOutcome::Winner(Player::P1);
+///
+/// // P2 has no pieces and 1 money, but their next upkeep pays for an infantry
+/// let alone = "3B/3./3./3b I2/3/3/3 2 C 3,1 0 0";
+/// let mut rules = RuleSet::default();
+/// assert!(matches!(outcome(alone, rules.clone(), "end"), ActionOutcome::Ongoing(_)));
+/// rules.base_income = 0;
+/// assert_eq!(outcome(alone, rules, "end"), winner);
+///
+/// // Stepping onto P2's baseline
+/// let near = "3B/3./3./3b 3/3/I2/2i 1 C 3,3 0 0";
+/// assert_eq!(outcome(near, RuleSet::default(), "c0-d0"), winner);
+///
+/// // P1's town raises their income to 3
+/// let town = "3B/2.T/3./3b I2/3/3/2i 1 C 3,3 0 0";
+/// let mut rules = RuleSet::default();
+/// assert!(matches!(outcome(town, rules.clone(), "end"), ActionOutcome::Ongoing(_)));
+/// rules.end_conditions.victory.income_threshold = Some(3);
+/// assert_eq!(outcome(town, rules, "end"), winner);
+///
+/// let mut rules = RuleSet::default();
+/// rules.end_conditions.victory.town_threshold = Some(1);
+/// assert_eq!(outcome(town, rules, "end"), winner);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VictoryConditions {
+    /// Win once the opponent has no pieces left and cannot afford to purchase one, even with the
+    /// income from their next upkeep
+    pub elimination: bool,
+    /// Win by standing on any tile of the opponent's baseline
+    pub baseline_occupation: bool,
+    /// Win once your income reaches this amount
+    pub income_threshold: Option<u8>,
+    /// Win once you own this many towns
+    pub town_threshold: Option<u8>,
+}
+
+impl Default for VictoryConditions {
+    fn default() -> Self {
+        Self {
+            elimination: true,
+            baseline_occupation: true,
+            income_threshold: None,
+            town_threshold: None,
+        }
+    }
+}
+
+/// The ways the game ends without a winner, checked after every action
+///
+/// ```
+/// use convoy::{rules::RuleSet, ActionOutcome, Game};
+///
+/// let position = "3B/3./3./3b I2/3/3/2i 1 C 3,3 0 0";
+/// let end_turns = |rules, turns| {
+///     let mut game = Game::from_position_string(position, rules).unwrap();
+///
+///     for _ in 1..turns {
+///         let ActionOutcome::Ongoing(next) = game.end_turn() else {
+///             panic!("The game goes on");
+///         };
+///         game = next;
+///     }
+///
+///     game.end_turn()
+/// };
+/// let draw_after = |rules: RuleSet, turns| {
+///     assert!(matches!(end_turns(rules.clone(), turns - 1), ActionOutcome::Ongoing(_)));
+///     assert_eq!(end_turns(rules, turns), ActionOutcome::Draw);
+/// };
+///
+/// let mut rules = RuleSet::default();
+/// rules.end_conditions.draw.turn_limit = Some(3);
+/// draw_after(rules, 3);
+///
+/// let mut rules = RuleSet::default();
+/// rules.end_conditions.draw.no_progress_limit = Some(4);
+/// draw_after(rules, 4);
+///
+/// // Without income the money stays the same, so every other turn repeats the first position
+/// let mut rules = RuleSet::default();
+/// rules.base_income = 0;
+/// rules.end_conditions.draw.repetition_limit = Some(2);
+/// draw_after(rules, 2);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DrawConditions {
+    /// Draw once this many turns have been played
+    pub turn_limit: Option<u16>,
+    /// Draw once this many turns in a row pass without a piece being purchased or destroyed, or a
+    /// town changing hands
+    pub no_progress_limit: Option<u16>,
+    /// Draw once the same position is reached at the start of a turn this many times
+    pub repetition_limit: Option<u8>,
+}
+
+impl Default for DrawConditions {
+    fn default() -> Self {
+        Self {
+            turn_limit: None,
+            no_progress_limit: Some(50),
+            repetition_limit: Some(3),
+        }
+    }
+}
+
+/// Which actions a player may take back
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UndoPolicy {
+    /// Any action can be undone, all the way back to the start of the game
+    #[default]
+    Unrestricted,
+    /// Only commands from the current turn can be undone, as suits competitive play
+    CurrentTurn,
+    Disabled,
+}
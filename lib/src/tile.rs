@@ -4,16 +4,47 @@ use crate::Player;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TileType {
     #[default]
     Regular,
-    Town,
+    Town(Option<Player>),
     Baseline(Player),
 }
 
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+impl TileType {
+    /// The letter written for the tile type in maps and position strings: `.` for regular, `N`
+    /// for an unowned town, `B` and `T` for [`Player::P1`]'s baseline and towns, or `b` and `t`
+    /// for [`Player::P2`]'s
+    #[must_use]
+    pub const fn letter(self) -> char {
+        match self {
+            Self::Regular => '.',
+            Self::Town(None) => 'N',
+            Self::Baseline(Player::P1) => 'B',
+            Self::Baseline(Player::P2) => 'b',
+            Self::Town(Some(Player::P1)) => 'T',
+            Self::Town(Some(Player::P2)) => 't',
+        }
+    }
+
+    /// The inverse of [`TileType::letter`]
+    #[must_use]
+    pub const fn from_letter(letter: char) -> Option<Self> {
+        match letter {
+            '.' => Some(Self::Regular),
+            'N' => Some(Self::Town(None)),
+            'B' => Some(Self::Baseline(Player::P1)),
+            'b' => Some(Self::Baseline(Player::P2)),
+            'T' => Some(Self::Town(Some(Player::P1))),
+            't' => Some(Self::Town(Some(Player::P2))),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Tile {
     pub piece_option: Option<Piece>,
@@ -24,18 +55,23 @@ impl Tile {
     #[must_use]
     pub const fn has_supplies(self, cur_player: Player) -> bool {
         match self.tile_type {
-            TileType::Baseline(owner) => matches!(
+            TileType::Baseline(owner) | TileType::Town(Some(owner)) => matches!(
                 (owner, cur_player),
                 (Player::P1, Player::P1) | (Player::P2, Player::P2)
             ),
-            TileType::Town => true,
-            TileType::Regular => false,
+            TileType::Town(None) | TileType::Regular => false,
         }
     }
 
     #[must_use]
-    pub const fn gives_income(self) -> bool {
-        matches!(self.tile_type, TileType::Town)
+    pub const fn gives_income(self, cur_player: Player) -> bool {
+        match self.tile_type {
+            TileType::Town(Some(owner)) => matches!(
+                (owner, cur_player),
+                (Player::P1, Player::P1) | (Player::P2, Player::P2)
+            ),
+            _ => false,
+        }
     }
 
     #[must_use]
@@ -48,4 +84,19 @@ impl Tile {
             _ => false,
         }
     }
+
+    /// Claims the tile for `player` if it is a town, returning whether it changed hands
+    pub const fn capture(&mut self, player: Player) -> bool {
+        match &mut self.tile_type {
+            TileType::Town(owner) if !matches!(
+                (*owner, player),
+                (Some(Player::P1), Player::P1) | (Some(Player::P2), Player::P2)
+            ) =>
+            {
+                *owner = Some(player);
+                true
+            }
+            _ => false,
+        }
+    }
 }
@@ -0,0 +1,182 @@
+use crate::{
+    coord::Coord,
+    history::Change,
+    pieces::{Piece, PieceType},
+    rng::Rng,
+    tile::{Tile, TileType},
+    Game, Phase, Player,
+};
+
+const TILE: u64 = 1 << 56;
+const PIECE: u64 = 2 << 56;
+const MONEY: u64 = 3 << 56;
+const PLAYER: u64 = 4 << 56;
+const PHASE: u64 = 5 << 56;
+
+impl Game {
+    /// A hash of the tiles, pieces, money, current player and phase which stays the same across
+    /// builds and platforms, so it can be compared between machines.
+    ///
+    /// It is kept up to date as the game changes rather than recomputed, and always equals
+    /// [`Game::compute_position_hash`].
+    ///
+    /// ```
+    /// use convoy::{rng::Rng, ActionOutcome, Game};
+    ///
+    /// let mut rng = Rng::new(0);
+    /// let mut game = Game::new();
+    ///
+    /// for _ in 0..500 {
+    ///     assert_eq!(game.position_hash(), game.compute_position_hash());
+    ///
+    ///     let actions = game.candidate_actions();
+    ///     let action = rng.choose(&actions).expect("Ending the turn is always legal");
+    ///
+    ///     match game.take_action(action.clone()) {
+    ///         Ok(ActionOutcome::Ongoing(next)) => game = next,
+    ///         _ => break,
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub const fn position_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// The position hash computed from scratch
+    ///
+    /// ```
+    /// use convoy::{
+    ///     coord::Coord, rules::RuleSet, tile::TileType, Action, ActionOutcome, Game, Player,
+    /// };
+    ///
+    /// // A recon next to a town, an artillery and an infantry able to beat the enemy infantry,
+    /// // which can retreat onto another town, and an infantry cut off from supplies
+    /// let position = "3B/3./2.N/.N./3b 1A1/I1R/1i1/I2/3 1 C 3,3 0 0";
+    /// let mut game = Game::from_position_string(position, RuleSet::default()).unwrap();
+    /// let tile = |game: &Game, coord: &str| game.board()[coord.parse::<Coord>().unwrap()];
+    ///
+    /// for action in ["b2-c2", "a1,b0-b1>c1/c1-d1", "end"] {
+    ///     let action: Action = action.parse().unwrap();
+    ///     let Ok(ActionOutcome::Ongoing(next)) = game.take_action(action) else {
+    ///         panic!("The actions are legal");
+    ///     };
+    ///     game = next;
+    ///
+    ///     assert_eq!(game.position_hash(), game.compute_position_hash());
+    /// }
+    ///
+    /// // The recon captured a town by moving, the infantry moved to attack, the beaten target
+    /// // retreated and captured a town, and the unsupplied infantry was destroyed at resupply
+    /// assert_eq!(tile(&game, "c2").tile_type, TileType::Town(Some(Player::P1)));
+    /// assert!(tile(&game, "b1").piece_option.is_some());
+    /// assert!(tile(&game, "c1").piece_option.is_none());
+    /// assert_eq!(tile(&game, "d1").tile_type, TileType::Town(Some(Player::P2)));
+    /// assert!(tile(&game, "d1").piece_option.is_some());
+    /// assert!(tile(&game, "d0").piece_option.is_none());
+    /// ```
+    #[must_use]
+    pub fn compute_position_hash(&self) -> u64 {
+        self.board
+            .coords()
+            .fold(0, |hash, coord| hash ^ tile_hash(coord, self.board[coord]))
+            ^ money_hash(Player::P1, self[Player::P1])
+            ^ money_hash(Player::P2, self[Player::P2])
+            ^ player_hash(self.cur_player)
+            ^ phase_hash(self.phase)
+    }
+
+    pub(crate) fn set_tile(&mut self, coord: Coord, tile: Tile) {
+        self.log(Change::Tile(coord, self.board[coord]));
+        self.hash ^= tile_hash(coord, self.board[coord]) ^ tile_hash(coord, tile);
+        self.board[coord] = tile;
+    }
+
+    pub(crate) fn set_piece(&mut self, coord: Coord, piece_option: Option<Piece>) {
+        self.set_tile(
+            coord,
+            Tile {
+                piece_option,
+                ..self.board[coord]
+            },
+        );
+    }
+
+    pub(crate) fn set_money(&mut self, player: Player, money: u8) {
+        self.log(Change::Money(player, self[player]));
+        self.hash ^= money_hash(player, self[player]) ^ money_hash(player, money);
+        match player {
+            Player::P1 => self.player_money[0] = money,
+            Player::P2 => self.player_money[1] = money,
+        }
+    }
+
+    pub(crate) fn set_cur_player(&mut self, player: Player) {
+        self.log(Change::Player(self.cur_player));
+        self.hash ^= player_hash(self.cur_player) ^ player_hash(player);
+        self.cur_player = player;
+    }
+
+    pub(crate) fn set_phase(&mut self, phase: Phase) {
+        self.log(Change::Phase(self.phase));
+        self.hash ^= phase_hash(self.phase) ^ phase_hash(phase);
+        self.phase = phase;
+    }
+}
+
+/// The key of a feature depends on nothing but the feature, so it never changes
+const fn key(feature: u64) -> u64 {
+    Rng::new(feature).next_u64()
+}
+
+fn tile_hash(Coord { rank, file }: Coord, tile: Tile) -> u64 {
+    let coord = (u64::from(rank) << 16) | (u64::from(file) << 8);
+    let tile_type = match tile.tile_type {
+        TileType::Regular => 0,
+        TileType::Town(None) => 1,
+        TileType::Town(Some(Player::P1)) => 2,
+        TileType::Town(Some(Player::P2)) => 3,
+        TileType::Baseline(Player::P1) => 4,
+        TileType::Baseline(Player::P2) => 5,
+    };
+
+    key(TILE | coord | tile_type)
+        ^ tile.piece_option.map_or(0, |piece| {
+            let piece_type = match piece.piece_type {
+                PieceType::Artillery => 0,
+                PieceType::Convoy => 1,
+                PieceType::Infantry => 2,
+                PieceType::Recon => 3,
+            };
+
+            key(PIECE
+                | coord
+                | (player_index(piece.owner) << 4)
+                | (piece_type << 1)
+                | u64::from(piece.exhausted))
+        })
+}
+
+fn money_hash(player: Player, money: u8) -> u64 {
+    key(MONEY | (player_index(player) << 8) | u64::from(money))
+}
+
+const fn player_hash(player: Player) -> u64 {
+    key(PLAYER | player_index(player))
+}
+
+const fn phase_hash(phase: Phase) -> u64 {
+    key(PHASE
+        | match phase {
+            Phase::Upkeep => 0,
+            Phase::Command => 1,
+            Phase::Resupply => 2,
+        })
+}
+
+const fn player_index(player: Player) -> u64 {
+    match player {
+        Player::P1 => 0,
+        Player::P2 => 1,
+    }
+}